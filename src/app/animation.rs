@@ -0,0 +1,356 @@
+use druid_shell::kurbo::{Point, Size, Rect};
+use druid_shell::piet::Color;
+use std::time::Duration;
+use crate::app::widget_graph::WidgetGraph;
+use crate::app::timer::TimerToken;
+
+/// A value which can be animated by Tween and Spring
+pub trait Animatable: Clone {
+    /// Returns the value between self (t = 0.0) and other (t = 1.0)
+    /// t can be outside of 0.0..1.0 since Springs overshoot their target
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Animatable for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Animatable for Point {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Animatable for Size {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Size::new(self.width.interpolate(&other.width, t),
+                  self.height.interpolate(&other.height, t))
+    }
+}
+
+impl Animatable for Rect {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Rect::new(self.x0.interpolate(&other.x0, t),
+                  self.y0.interpolate(&other.y0, t),
+                  self.x1.interpolate(&other.x1, t),
+                  self.y1.interpolate(&other.y1, t))
+    }
+}
+
+impl Animatable for Color {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let from = self.as_rgba_u32();
+        let to = other.as_rgba_u32();
+
+        let channel = |shift: u32| {
+            let a = ((from >> shift) & 0xff) as f64;
+            let b = ((to >> shift) & 0xff) as f64;
+            a.interpolate(&b, t).round().max(0.0).min(255.0) as u8
+        };
+
+        Color::rgba8(channel(24), channel(16), channel(8), channel(0))
+    }
+}
+
+/// The progress curve of a Tween
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => if t < 0.5 {
+                2.0 * t * t
+            } else {
+                -1.0 + (4.0 - 2.0 * t) * t
+            },
+        }
+    }
+}
+
+/// Animates a value from a start to an end value in a fixed time
+#[derive(Clone)]
+pub struct Tween<T: Animatable> {
+    from: T,
+    to: T,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl<T: Animatable> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Tween {
+            from,
+            to,
+            duration,
+            elapsed: Duration::from_secs(0),
+            easing: Easing::EaseInOut,
+        }
+    }
+    /// Creates a finished Tween, which rests at value
+    pub fn idle(value: T) -> Self {
+        Tween::new(value.clone(), value, Duration::from_secs(0))
+    }
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// Starts a new animation from the current value to the new target
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = Duration::from_secs(0);
+    }
+    /// Advances the animation, returns true if the animation is still running
+    pub fn advance(&mut self, interval: Duration) -> bool {
+        self.elapsed = (self.elapsed + interval).min(self.duration);
+        !self.is_done()
+    }
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+    pub fn value(&self) -> T {
+        if self.is_done() {
+            return self.to.clone();
+        }
+        let t = self.elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        self.from.interpolate(&self.to, self.easing.apply(t))
+    }
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+}
+
+/// Animates a value with a damped spring
+/// The spring moves the progress from the start (0.0) to the target (1.0) and can overshoot
+#[derive(Clone)]
+pub struct Spring<T: Animatable> {
+    from: T,
+    to: T,
+    position: f64,
+    velocity: f64,
+    stiffness: f64,
+    damping: f64,
+}
+
+impl<T: Animatable> Spring<T> {
+    /// The distance to the target below which the spring comes to rest
+    const REST: f64 = 0.001;
+
+    pub fn new(from: T, to: T) -> Self {
+        Spring {
+            from,
+            to,
+            position: 0.0,
+            velocity: 0.0,
+            stiffness: 170.0,
+            damping: 26.0,
+        }
+    }
+    /// Creates a spring, which rests at value
+    pub fn idle(value: T) -> Self {
+        let mut spring = Spring::new(value.clone(), value);
+        spring.position = 1.0;
+        spring
+    }
+    pub fn stiffness(mut self, stiffness: f64) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+    pub fn damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+    /// Starts a new animation from the current value to the new target
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.position = 0.0;
+        self.velocity = 0.0;
+    }
+    /// Advances the animation, returns true if the spring is still moving
+    pub fn advance(&mut self, interval: Duration) -> bool {
+        //Integrate in small steps to keep stiff springs stable on slow frames
+        let mut remaining = interval.as_secs_f64();
+        while remaining > 0.0 && !self.is_done() {
+            let step = remaining.min(1.0 / 240.0);
+            let force = self.stiffness * (1.0 - self.position) - self.damping * self.velocity;
+            self.velocity += force * step;
+            self.position += self.velocity * step;
+            remaining -= step;
+        }
+        if self.is_done() {
+            self.position = 1.0;
+            self.velocity = 0.0;
+        }
+        !self.is_done()
+    }
+    pub fn is_done(&self) -> bool {
+        (1.0 - self.position).abs() < Self::REST && self.velocity.abs() < Self::REST
+    }
+    pub fn value(&self) -> T {
+        self.from.interpolate(&self.to, self.position)
+    }
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+}
+
+/// A Clock for headless tests, which drives the animations of a WidgetGraph without a Window
+pub struct TestClock {
+    elapsed: Duration,
+    frame: Duration,
+    timers: Vec<(TimerToken, Duration)>,
+}
+
+impl TestClock {
+    /// Creates a clock delivering a frame every frame-duration
+    pub fn new(frame: Duration) -> Self {
+        TestClock {
+            elapsed: Duration::from_secs(0),
+            frame,
+            timers: Vec::new(),
+        }
+    }
+    /// Creates a clock running at 60 frames per second
+    pub fn sixty_fps() -> Self {
+        TestClock::new(Duration::from_nanos(16_666_667))
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+    /// Advances the time by the given amount
+    /// Sends an AnimFrame to the graph for every frame in between, as long as it requests them
+    /// and fires the timers which ran out
+    pub fn advance(&mut self, graph: &mut WidgetGraph, by: Duration) {
+        let end = self.elapsed + by;
+        self.schedule(graph);
+        while self.elapsed < end {
+            let step = self.frame.min(end - self.elapsed);
+            self.elapsed += step;
+            if graph.wants_anim_frame() {
+                graph.anim_frame(step);
+            }
+            self.fire_timers(graph);
+        }
+    }
+    fn schedule(&mut self, graph: &mut WidgetGraph) {
+        let now = self.elapsed;
+        self.timers.extend(graph.take_timer_requests()
+            .into_iter()
+            .map(|(token, delay)|(token, now + delay)));
+    }
+    fn fire_timers(&mut self, graph: &mut WidgetGraph) {
+        self.schedule(graph);
+        let now = self.elapsed;
+        let (due, pending): (Vec<_>, Vec<_>) = self.timers.drain(..).partition(|(_, deadline)|*deadline <= now);
+        self.timers = pending;
+        for (token, _) in due {
+            graph.timer(token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::Widget;
+    use crate::app::widget_graph::WidgetContext;
+    use crate::app::event::{Event, EventResponse, Change};
+    use crate::app::size::PrefSize;
+    use crate::state::key::Key;
+    use druid_shell::piet::Piet;
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+    /// Advances the Tween on every frame, until it is done
+    struct Animated(Rc<RefCell<Tween<f64>>>);
+
+    impl Widget<()> for Animated {
+        fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &()) {}
+
+        fn handle_event(&mut self, event: Event, mut context: WidgetContext, data: Key<()>) -> EventResponse {
+            if let Event::AnimFrame(interval) = event {
+                if self.0.borrow_mut().advance(interval) {
+                    context.request_anim_frame();
+                }
+            }
+            EventResponse::NONE
+        }
+
+        fn get_pref_size(&mut self, context: WidgetContext, data: &()) -> PrefSize {
+            PrefSize::zero()
+        }
+
+        fn layout(&mut self, size: Size, context: WidgetContext, data: &()) {}
+
+        fn build(&mut self, mut context: WidgetContext) {
+            context.request_anim_frame();
+        }
+
+        fn update(&mut self, new: &(), old: Option<&()>) -> Change {
+            Change::None
+        }
+    }
+
+    #[test]
+    fn tween_reaches_its_endpoints() {
+        let mut tween = Tween::new(2.0, 6.0, Duration::from_millis(100)).easing(Easing::Linear);
+        assert_eq!(tween.value(), 2.0);
+
+        assert!(tween.advance(Duration::from_millis(50)));
+        assert!((tween.value() - 4.0).abs() < 1e-9);
+
+        //Frames after the end don't overshoot
+        assert!(!tween.advance(Duration::from_millis(80)));
+        assert!(tween.is_done());
+        assert_eq!(tween.value(), 6.0);
+    }
+
+    #[test]
+    fn spring_settles() {
+        let mut spring = Spring::new(0.0, 10.0);
+        let mut frames = 0;
+        while spring.advance(FRAME) {
+            frames += 1;
+            assert!(frames < 120, "the spring didn't settle within two seconds");
+        }
+        assert_eq!(spring.value(), 10.0);
+
+        //A retargeted spring starts at its current value
+        spring.retarget(-5.0);
+        assert_eq!(spring.value(), 10.0);
+        assert!(spring.advance(FRAME));
+        assert!(spring.value() < 10.0);
+    }
+
+    #[test]
+    fn test_clock_drives_anim_frames() {
+        let tween = Rc::new(RefCell::new(Tween::new(0.0, 10.0, Duration::from_millis(500)).easing(Easing::Linear)));
+        let mut graph = WidgetGraph::new(Animated(tween.clone()));
+        let mut clock = TestClock::sixty_fps();
+
+        clock.advance(&mut graph, Duration::from_millis(250));
+        assert_eq!(clock.elapsed(), Duration::from_millis(250));
+        assert!((tween.borrow().value() - 5.0).abs() < 1e-6);
+        assert!(graph.wants_anim_frame());
+
+        clock.advance(&mut graph, Duration::from_secs(1));
+        assert_eq!(tween.borrow().value(), 10.0);
+        assert!(!graph.wants_anim_frame());
+    }
+}
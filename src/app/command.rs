@@ -0,0 +1,190 @@
+use crate::state::CloneState;
+use druid_shell::{KeyEvent, KeyCode};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Identifies an action triggered by a menu item, a shortcut or a Widget
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct Command(pub &'static str);
+
+impl Command {
+    /// Moves the focus to the next Widget, handled by the Window if it has no handler
+    pub const FOCUS_NEXT: Command = Command("focus-next");
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+/// A command of the Window with its handler
+pub struct CommandDesc {
+    command: Command,
+    handler: Box<dyn FnMut()>,
+    enabled: Option<Box<dyn Fn() -> bool>>,
+}
+
+impl CommandDesc {
+    pub fn new(command: Command, handler: impl FnMut() + 'static) -> Self {
+        CommandDesc {
+            command,
+            handler: Box::new(handler),
+            enabled: None,
+        }
+    }
+    /// A command which changes the value of the State
+    pub fn on_state<T: Clone + Send + Sync + 'static>(command: Command, state: &CloneState<T>, mut handler: impl FnMut(&mut T) + 'static) -> Self {
+        let handle = state.handle();
+        Self::new(command, move||{
+            handle.update(|value|handler(value));
+        })
+    }
+    /// The command is ignored while the predicate returns false
+    pub fn enabled(mut self, predicate: impl Fn() -> bool + 'static) -> Self {
+        self.enabled = Some(Box::new(predicate));
+        self
+    }
+}
+
+/// The commands of a Window
+#[derive(Default)]
+pub(crate) struct CommandRegistry {
+    commands: HashMap<Command, CommandDesc>,
+}
+
+impl CommandRegistry {
+    pub(crate) fn insert(&mut self, desc: CommandDesc) {
+        self.commands.insert(desc.command, desc);
+    }
+    /// Commands without a handler are always enabled
+    pub(crate) fn is_enabled(&self, command: Command) -> bool {
+        self.commands.get(&command)
+            .and_then(|desc|desc.enabled.as_ref())
+            .map_or(true, |enabled|enabled())
+    }
+    /// Calls the handler if the command is registered, returns whether it was handled
+    pub(crate) fn execute(&mut self, command: Command) -> bool {
+        match self.commands.get_mut(&command) {
+            Some(desc) => {
+                (desc.handler)();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A key with its modifiers
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Shortcut {
+    key: KeyCode,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+impl Shortcut {
+    pub fn new(key: KeyCode) -> Self {
+        Shortcut {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+            meta: false,
+        }
+    }
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+    pub fn meta(mut self) -> Self {
+        self.meta = true;
+        self
+    }
+    /// The modifiers have to match exactly, Ctrl+Tab doesn't match Tab
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        event.key_code == self.key &&
+            event.mods.ctrl == self.ctrl &&
+            event.mods.shift == self.shift &&
+            event.mods.alt == self.alt &&
+            event.mods.meta == self.meta
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (pressed, name) in [(self.ctrl, "Ctrl+"), (self.shift, "Shift+"), (self.alt, "Alt+"), (self.meta, "Meta+")].iter() {
+            if *pressed {
+                f.write_str(name)?;
+            }
+        }
+        let key = format!("{:?}", self.key);
+        f.write_str(key.strip_prefix("Key").or_else(||key.strip_prefix("Digit")).unwrap_or(&key))
+    }
+}
+
+/// Maps shortcuts to commands
+/// Store it in a CloneState to change the bindings of a Window at runtime.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(Shortcut, Command)>,
+}
+
+impl Keymap {
+    /// A Keymap without bindings
+    pub fn new() -> Self {
+        Keymap {
+            bindings: Vec::new(),
+        }
+    }
+    /// Binds the shortcut to the command, replacing its previous binding
+    pub fn bind(mut self, shortcut: Shortcut, command: Command) -> Self {
+        self.set(shortcut, command);
+        self
+    }
+    pub fn set(&mut self, shortcut: Shortcut, command: Command) {
+        self.remove(shortcut);
+        self.bindings.push((shortcut, command));
+    }
+    pub fn remove(&mut self, shortcut: Shortcut) {
+        self.bindings.retain(|(bound, _)|*bound != shortcut);
+    }
+    /// Returns the command bound to the pressed key
+    pub fn command(&self, event: &KeyEvent) -> Option<Command> {
+        self.bindings.iter()
+            .find(|(shortcut, _)|shortcut.matches(event))
+            .map(|(_, command)|*command)
+    }
+    /// Returns all shortcuts of the command
+    pub fn shortcuts(&self, command: Command) -> impl Iterator<Item=Shortcut> + '_ {
+        self.bindings.iter()
+            .filter(move|(_, bound)|*bound == command)
+            .map(|(shortcut, _)|*shortcut)
+    }
+    /// Returns all bindings in the order they were bound, e.g. for a help overlay
+    pub fn bindings(&self) -> &[(Shortcut, Command)] {
+        &self.bindings
+    }
+    /// Returns the text of every shortcut with the name of its command
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        self.bindings.iter()
+            .map(|(shortcut, command)|(shortcut.to_string(), command.name()))
+            .collect()
+    }
+}
+
+impl Default for Keymap {
+    /// The default bindings of a Window
+    fn default() -> Self {
+        Keymap::new()
+            .bind(Shortcut::new(KeyCode::Tab), Command::FOCUS_NEXT)
+    }
+}
@@ -0,0 +1,23 @@
+use druid_shell::kurbo::{Rect, Size, Point, Vec2};
+use crate::app::overlay::OverlayPainter;
+use std::any::Any;
+
+/// The distance of the preview to the mouse
+const PREVIEW_OFFSET: Vec2 = Vec2 { x: 8.0, y: 8.0 };
+
+/// An in-app drag and drop, see WidgetContext::start_drag
+pub(crate) struct Drag {
+    /// None after a drop target accepted it
+    pub(crate) payload: Option<Box<dyn Any>>,
+    /// the node of the Widget which started the drag, it receives Event::DragEnded
+    pub(crate) source: u32,
+    pub(crate) preview_size: Size,
+    pub(crate) preview: OverlayPainter,
+}
+
+impl Drag {
+    /// Returns the bounds of the preview in window coordinates
+    pub(crate) fn preview_rect(&self, mouse: Point) -> Rect {
+        Rect::from_origin_size(mouse + PREVIEW_OFFSET, self.preview_size)
+    }
+}
@@ -1,5 +1,9 @@
 use druid_shell::{MouseEvent, KeyEvent};
 use druid_shell::kurbo::{Vec2, Rect, Size};
+use std::time::Duration;
+use crate::app::timer::TimerToken;
+use crate::app::overlay::OverlayToken;
+use crate::app::menu::Command;
 
 #[derive(Clone)]
 pub enum Event{
@@ -8,12 +12,37 @@ pub enum Event{
     MouseMove(MouseEvent),
     MouseDown(MouseEvent),
     MouseUp(MouseEvent),
+    /// The mouse wheel or touchpad scrolled, the delta is MouseEvent::wheel_delta
+    Wheel(MouseEvent),
     MouseExit,
 
     //Keyboard
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
 
+    //Time
+    /// Send to every Widget once per frame after one of them requested an animation frame,
+    /// contains the time elapsed since the last frame
+    AnimFrame(Duration),
+    /// Send to the StateRoot of the Widget, which requested the timer
+    Timer(TimerToken),
+
+    //Popups
+    /// An Event of the popup, send to the StateRoot of the Widget which opened it
+    /// Mouse positions are relative to the popup, key events are send here while it is open
+    Overlay(OverlayToken, Box<Event>),
+    /// The popup was closed by the window, e.g. by a click outside of it
+    OverlayClosed(OverlayToken),
+
+    //Commands
+    /// A command without a handler of the window, send to every Widget
+    /// Commands of a context menu are only send to the StateRoot of the Widget which showed it.
+    Command(Command),
+
+    //Drag and drop
+    /// The drag started by the Widget ended, true if a drop target accepted the payload
+    /// Send to the StateRoot of the Widget which started the drag.
+    DragEnded(bool),
 }
 
 impl Event {
@@ -23,22 +52,62 @@ impl Event {
             Event::MouseMove(me) => {me}
             Event::MouseDown(me) => {me}
             Event::MouseUp(me) => {me}
+            Event::Wheel(me) => {me}
             _ => {return true}
         };
         me.pos -= amount;
         me.pos.x >= 0.0 && me.pos.y >= 0.0 && new_size.width >= me.pos.x && new_size.height >= me.pos.y
     }
+    /// Returns true if the Event should be delivered to every Widget instead of only the focused
+    /// or hovered one
+    pub fn is_broadcast(&self) -> bool {
+        match self {
+            Event::AnimFrame(_) => true,
+            Event::Timer(_) => true,
+            Event::Overlay(_, _) => true,
+            Event::OverlayClosed(_) => true,
+            Event::Command(_) => true,
+            Event::DragEnded(_) => true,
+            _ => false,
+        }
+    }
     pub fn mouse_event(&self) -> Option<&MouseEvent> {
         match self {
             Event::MouseEnter(me) => {Some(me)}
             Event::MouseMove(me) => {Some(me)}
             Event::MouseDown(me) => {Some(me)}
             Event::MouseUp(me) => {Some(me)}
+            Event::Wheel(me) => {Some(me)}
             _ => None
         }
     }
 }
 
+/// Notifications about the lifecycle of a Widget, see Widget::lifecycle
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Lifecycle {
+    /// The Widget was inserted into the widget-tree and built
+    WidgetAdded,
+    /// The Widget is about to be removed from the widget-tree
+    WidgetRemoved,
+    /// The Widget became visible
+    Shown,
+    /// The Widget is hidden, it wont be drawn or receive Events until it is shown again
+    Hidden,
+    /// The Widget got a new size, this is only send to the Widget itself, not its children
+    SizeChanged(Size),
+}
+
+impl Lifecycle {
+    /// Returns true if the notification concerns all descendants of the receiving Widget
+    pub fn is_broadcast(&self) -> bool {
+        match self {
+            Lifecycle::SizeChanged(_) => false,
+            _ => true,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum Change {
     None,
@@ -0,0 +1,140 @@
+use crate::state::{CloneState, Handle, StateID};
+use druid_shell::{HotKey, Menu};
+use std::collections::HashMap;
+pub use crate::app::command::Command;
+
+/// A clickable entry of a menu, which dispatches its command
+#[derive(Clone)]
+pub struct MenuItem {
+    title: String,
+    command: Command,
+    hotkey: Option<HotKey>,
+    enabled: Option<Handle<bool>>,
+    checked: Option<Handle<bool>>,
+}
+
+impl MenuItem {
+    pub fn new(title: impl Into<String>, command: Command) -> Self {
+        MenuItem {
+            title: title.into(),
+            command,
+            hotkey: None,
+            enabled: None,
+            checked: None,
+        }
+    }
+    /// The accelerator shown next to the item, the platform triggers the item by it
+    pub fn hotkey(mut self, hotkey: HotKey) -> Self {
+        self.hotkey = Some(hotkey);
+        self
+    }
+    /// Enables the item while the State is true
+    pub fn enabled(mut self, enabled: &CloneState<bool>) -> Self {
+        self.enabled = Some(enabled.handle());
+        self
+    }
+    /// Shows a checkmark while the State is true
+    pub fn checked(mut self, checked: &CloneState<bool>) -> Self {
+        self.checked = Some(checked.handle());
+        self
+    }
+}
+
+#[derive(Clone)]
+enum MenuEntry {
+    Item(MenuItem),
+    Submenu(MenuDesc),
+    Separator,
+}
+
+/// The description of a menu bar, a submenu or a context menu
+#[derive(Clone)]
+pub struct MenuDesc {
+    title: String,
+    entries: Vec<MenuEntry>,
+}
+
+impl MenuDesc {
+    /// The title is shown for submenus, it is ignored for the menu bar and context menus
+    pub fn new(title: impl Into<String>) -> Self {
+        MenuDesc {
+            title: title.into(),
+            entries: Vec::new(),
+        }
+    }
+    pub fn item(mut self, item: MenuItem) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+        self
+    }
+    pub fn submenu(mut self, menu: MenuDesc) -> Self {
+        self.entries.push(MenuEntry::Submenu(menu));
+        self
+    }
+    pub fn separator(mut self) -> Self {
+        self.entries.push(MenuEntry::Separator);
+        self
+    }
+
+    /// Returns the States the menu depends on
+    pub(crate) fn states(&self) -> Vec<StateID> {
+        let mut states = Vec::new();
+        for entry in self.entries.iter() {
+            match entry {
+                MenuEntry::Item(item) => {
+                    states.extend(item.enabled.iter().chain(item.checked.iter()).map(Handle::id));
+                }
+                MenuEntry::Submenu(menu) => states.extend(menu.states()),
+                MenuEntry::Separator => {}
+            }
+        }
+        states
+    }
+
+    /// Builds the native menu, the ids of the items are registered in commands
+    pub(crate) fn build(&self, popup: bool, commands: &mut MenuCommands) -> Menu {
+        let mut menu = if popup {Menu::new_for_popup()} else {Menu::new()};
+        for entry in self.entries.iter() {
+            match entry {
+                MenuEntry::Item(item) => {
+                    let id = commands.insert(item.command);
+                    //Dropped States disable the item
+                    let enabled = item.enabled.as_ref().map_or(true, |enabled|enabled.get().unwrap_or(false));
+                    let checked = item.checked.as_ref().map_or(false, |checked|checked.get().unwrap_or(false));
+                    menu.add_item(id, &item.title, item.hotkey.as_ref(), enabled, checked);
+                }
+                MenuEntry::Submenu(submenu) => {
+                    menu.add_dropdown(submenu.build(false, commands), &submenu.title, true);
+                }
+                MenuEntry::Separator => menu.add_separator(),
+            }
+        }
+        menu
+    }
+}
+
+/// Maps the ids of native menu items to their commands
+pub(crate) struct MenuCommands {
+    next: u32,
+    commands: HashMap<u32, Command>,
+}
+
+impl MenuCommands {
+    /// The menu bar and context menus use separate ranges of ids
+    pub(crate) fn new(first: u32) -> Self {
+        MenuCommands {
+            next: first,
+            commands: HashMap::new(),
+        }
+    }
+    fn insert(&mut self, command: Command) -> u32 {
+        self.next += 1;
+        self.commands.insert(self.next, command);
+        self.next
+    }
+    pub(crate) fn get(&self, id: u32) -> Option<Command> {
+        self.commands.get(&id).copied()
+    }
+    pub(crate) fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
@@ -1,5 +1,11 @@
+pub mod animation;
+pub mod command;
 pub mod context;
+pub mod drag;
 pub mod event;
+pub mod menu;
+pub mod overlay;
 pub mod size;
+pub mod timer;
 pub mod widget_graph;
 pub mod window;
\ No newline at end of file
@@ -0,0 +1,27 @@
+use druid_shell::Counter;
+use druid_shell::kurbo::{Rect, Size};
+use druid_shell::piet::Piet;
+
+/// Identifies a popup opened by a Widget, see WidgetContext::open_overlay
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct OverlayToken(u64);
+
+impl OverlayToken {
+    pub(crate) fn next() -> Self {
+        static TOKENS: Counter = Counter::new();
+        OverlayToken(TOKENS.next())
+    }
+}
+
+/// Paints the content of a popup, the origin is the top left corner of the popup
+pub type OverlayPainter = Box<dyn FnMut(&mut Piet, Size)>;
+
+/// A popup above all Widgets of a window
+pub(crate) struct Overlay {
+    pub(crate) token: OverlayToken,
+    /// the node of the owning Widget, which receives the events of the popup
+    pub(crate) owner: u32,
+    /// the bounds in window coordinates
+    pub(crate) rect: Rect,
+    pub(crate) painter: OverlayPainter,
+}
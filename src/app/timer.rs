@@ -0,0 +1,12 @@
+use druid_shell::Counter;
+
+/// Identifies a timer requested by a Widget
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct TimerToken(u64);
+
+impl TimerToken {
+    pub(crate) fn next() -> Self {
+        static TOKENS: Counter = Counter::new();
+        TimerToken(TOKENS.next())
+    }
+}
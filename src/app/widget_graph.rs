@@ -1,15 +1,25 @@
-use crate::widgets::{StateWidget, Widget, StateWidgetImpl};
-use druid_shell::kurbo::{Point, Size, Rect};
+use crate::widgets::{StateWidget, Widget, StateWidgetImpl, RemovedStateWidget};
+use druid_shell::kurbo::{Point, Size, Rect, Affine};
 use crate::app::size::PrefSize;
 use pool_tree::child_unique::ChildUniq;
-use druid_shell::piet::Piet;
-use crate::app::event::{EventResponse, Event, Change};
+use druid_shell::piet::{Piet, RenderContext};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
 use pool_tree::reference::{Ref, TreeRef};
-use crate::state::{StateID, CloneState};
+use crate::state::{StateID, CloneState, Task};
 use pool_tree::ref_unique::RefUniq;
 use std::collections::HashMap;
 use pool_tree::children_unique::ChildrenUnique;
 use pool_tree::tree::Tree;
+use std::time::Duration;
+use std::collections::HashSet;
+use crate::app::timer::TimerToken;
+use crate::app::overlay::{Overlay, OverlayToken, OverlayPainter};
+use std::future::Future;
+use druid_shell::Cursor;
+use crate::app::menu::{MenuDesc, Command};
+use crate::app::command::CommandRegistry;
+use crate::app::drag::Drag;
+use std::any::Any;
 
 pub struct StateRoot {
     widgets: Box<dyn StateWidget>,
@@ -18,6 +28,8 @@ pub struct StateRoot {
     last_update: u64,
     pref_size: PrefSize,
     re_layout: bool,
+    /// the slot of a removed node is reused by the next insert into its parent
+    removed: bool,
 }
 
 impl StateRoot {
@@ -29,6 +41,7 @@ impl StateRoot {
             last_update: 0,
             pref_size: PrefSize::zero(),
             re_layout: true,
+            removed: false,
         };
         //TODO: move this to the appropriate position
         //For now this ensures, that every Widget is update at its inserting in the widget-tree
@@ -38,42 +51,87 @@ impl StateRoot {
     }
 
     pub fn draw(mut self: ChildUniq<Self>, piet: &mut Piet, size: Size, dirty_rect: Rect, env: Env) {
+        let node = self.index();
         let (this, childs) = self.get_both_unique();
-        this.widgets.draw(piet, size, dirty_rect, WidgetContext::new(childs, env))
+        this.widgets.draw(piet, size, dirty_rect, WidgetContext::new(childs, env.at(node)))
     }
     pub fn handle_event(mut self: ChildUniq<Self>, event: Event, env: Env) -> EventResponse {
+        let node = self.index();
         let (this, childs) = self.get_both_unique();
-        let response = this.widgets.handle_event(event, WidgetContext::new(childs, env));
+        let response = this.widgets.handle_event(event, WidgetContext::new(childs, env.at(node)));
         self.re_layout |= response.change().bounds_changed();
         response
     }
     pub fn get_pref_size(mut self: ChildUniq<Self>, env: Env) -> PrefSize {
         if self.re_layout {
+            let node = self.index();
             let (this, childs) = self.get_both_unique();
-            let pref = this.widgets.get_pref_size(WidgetContext::new(childs, env));
+            let pref = this.widgets.get_pref_size(WidgetContext::new(childs, env.at(node)));
             self.pref_size = pref;
         }
         self.pref_size
     }
     pub fn layout(mut self: ChildUniq<Self>, size: Size, env: Env) {
         if self.re_layout || self.size != size {
+            let resized = self.size != size;
             self.size = size;
+            let node = self.index();
             let (this, childs) = self.get_both_unique();
-            this.widgets.layout(size, WidgetContext::new(childs, env));
+            let mut context = WidgetContext::new(childs, env.at(node));
+            if resized {
+                this.widgets.lifecycle(&Lifecycle::SizeChanged(size), context.id());
+            }
+            this.widgets.layout(size, context);
             self.re_layout = false;
         }
     }
     pub fn traverse_focus(mut self: ChildUniq<Self>, env: Env) -> bool {
+        let node = self.index();
+        let (this, childs) = self.get_both_unique();
+        this.widgets.traverse_focus(WidgetContext::new(childs, env.at(node)))
+    }
+    pub fn lifecycle(mut self: ChildUniq<Self>, event: &Lifecycle, env: Env) {
+        let node = self.index();
         let (this, childs) = self.get_both_unique();
-        this.widgets.traverse_focus(WidgetContext::new(childs, env))
+        this.widgets.lifecycle(event, WidgetContext::new(childs, env.at(node)))
     }
     pub fn build(mut self: ChildUniq<Self>, env: Env) {
+        let node = self.index();
         let (this, childs) = self.get_both_unique();
-        this.widgets.build(WidgetContext::new(childs, env))
+        this.widgets.build(WidgetContext::new(childs, env.at(node)))
     }
     pub fn states<'a>(self: Ref<'a, Self>) -> StateID {
         self.widgets.states()
     }
+    /// Drops the Widgets of this node and all of its descendants and forgets everything they
+    /// registered in the graph
+    ///
+    /// The nodes stay in the pool, since the StateWrappers of the following siblings store their
+    /// child index. Their slots are reused by the next insert into the same parent.
+    pub fn remove_subtree(mut self: ChildUniq<Self>, mut env: Env) {
+        if self.removed {
+            return;
+        }
+        let node = self.index();
+        let (this, mut childs) = self.get_both_unique();
+
+        for index in 0..childs.count() {
+            let child = childs.get_child_unique(index);
+            if !child.removed {
+                child.remove_subtree(env.id());
+            }
+        }
+
+        //The root has no slot in its parent
+        let is_root = !env.data.parents.contains_key(&node);
+        env.data.remove_node(node, this.widgets.states());
+        this.widgets = Box::new(RemovedStateWidget::new());
+        this.re_layout = true;
+        this.removed = true;
+        if !is_root {
+            env.data.free_slots += 1;
+        }
+    }
 
     pub fn update(mut self: RefUniq<Self>) -> Change {
 
@@ -94,21 +152,133 @@ impl StateRoot {
     }
 }
 
+/// The data of a WidgetGraph, which is shared by all of its StateRoots
+#[derive(Default)]
+pub struct GraphData {
+    dependent_nodes: HashMap<StateID, Vec<u32>>,
+    /// the parent of every node except the root
+    parents: HashMap<u32, u32>,
+    /// the number of removed nodes, whose slots in the pool wait for reuse
+    free_slots: usize,
+    anim_frame: bool,
+    /// the owning node of every running timer
+    timers: HashMap<TimerToken, u32>,
+    timer_requests: Vec<(TimerToken, Duration)>,
+    /// the Tasks spawned by every node
+    tasks: HashMap<u32, Vec<Task>>,
+    /// if set, targeted events are only delivered to these nodes
+    route: Option<HashSet<u32>>,
+    overlay: Option<Overlay>,
+    /// the popups closed by the graph, whose owners have to be notified
+    closed_overlays: Vec<(OverlayToken, u32)>,
+    /// the area which changed by opening, updating or closing a popup
+    overlay_dirty: Option<Rect>,
+    /// the cursor requested during the last event
+    cursor: Option<Cursor>,
+    /// the context menu requested during the last event with its position and owner
+    context_menu: Option<(MenuDesc, Point, u32)>,
+    /// the commands submitted by Widgets during the last event with the StateRoot they are
+    /// limited to
+    commands: Vec<(Command, Option<u32>)>,
+    /// the handlers and enabled predicates of the commands of the window
+    registry: CommandRegistry,
+    /// the tooltip is drawn above the popup and doesn't receive events
+    tooltip: Option<Overlay>,
+    window_size: Size,
+    drag: Option<Drag>,
+    /// the last position of the mouse in window coordinates
+    mouse_pos: Point,
+    /// true from a consumed MouseDown until the MouseUp
+    captured: bool,
+    /// the mouse left the window while it was captured
+    exit_pending: bool,
+}
+
+impl GraphData {
+    /// Returns the node and all of its ancestors
+    fn path(&self, mut node: u32) -> HashSet<u32> {
+        let mut path = HashSet::new();
+        path.insert(node);
+        while let Some(parent) = self.parents.get(&node) {
+            node = *parent;
+            path.insert(node);
+        }
+        path
+    }
+    /// Forgets everything owned by the node
+    fn remove_node(&mut self, node: u32, state: StateID) {
+        if let Some(nodes) = self.dependent_nodes.get_mut(&state) {
+            nodes.retain(|other|*other != node);
+            if nodes.is_empty() {
+                self.dependent_nodes.remove(&state);
+            }
+        }
+        self.parents.remove(&node);
+        self.timers.retain(|_, owner|*owner != node);
+        if let Some(tasks) = self.tasks.remove(&node) {
+            tasks.iter().for_each(Task::cancel);
+        }
+        if self.overlay.as_ref().map_or(false, |overlay|overlay.owner == node) {
+            self.remove_overlay();
+        }
+    }
+    fn mark_overlay_dirty(&mut self, rect: Rect) {
+        self.overlay_dirty = Some(self.overlay_dirty.map_or(rect, |old|old.union(rect)));
+    }
+    fn remove_overlay(&mut self) -> Option<Overlay> {
+        let overlay = self.overlay.take()?;
+        self.mark_overlay_dirty(overlay.rect);
+        Some(overlay)
+    }
+    /// Moves the preview of the drag with the mouse
+    fn move_mouse(&mut self, pos: Point) {
+        if let Some(drag) = self.drag.as_ref() {
+            let old = drag.preview_rect(self.mouse_pos);
+            let new = drag.preview_rect(pos);
+            self.mark_overlay_dirty(old.union(new));
+        }
+        self.mouse_pos = pos;
+    }
+    fn remove_tooltip(&mut self) -> Option<Overlay> {
+        let tooltip = self.tooltip.take()?;
+        self.mark_overlay_dirty(tooltip.rect);
+        Some(tooltip)
+    }
+    /// Hides the tooltip without a request of its owner, the owner receives Event::OverlayClosed
+    fn close_tooltip(&mut self) {
+        if let Some(tooltip) = self.remove_tooltip() {
+            self.closed_overlays.push((tooltip.token, tooltip.owner));
+        }
+    }
+}
+
 pub struct Env<'a> {
-    dependent_nodes: &'a mut HashMap<StateID, Vec<u32>>,
+    data: &'a mut GraphData,
+    node: u32,
 }
 
 impl<'a> Env<'a> {
-    pub fn new(dependent_nodes: &'a mut HashMap<StateID, Vec<u32>>) -> Self {
+    pub fn new(data: &'a mut GraphData) -> Self {
         Env {
-            dependent_nodes
+            data,
+            node: 0,
         }
     }
     pub fn id(&mut self) -> Env {
         Env {
-            dependent_nodes: self.dependent_nodes,
+            data: self.data,
+            node: self.node,
         }
     }
+    /// Returns the Env of the given node
+    fn at(mut self, node: u32) -> Self {
+        self.node = node;
+        self
+    }
+    /// Returns true if the node should receive the current event
+    pub(crate) fn routes_to(&self, node: u32) -> bool {
+        self.data.route.as_ref().map_or(true, |route|route.contains(&node))
+    }
 }
 
 pub struct WidgetContext<'a> {
@@ -132,24 +302,226 @@ impl<'a> WidgetContext<'a> {
     pub fn env(&mut self) -> Env {
         self.env.id()
     }
+    /// Requests an Event::AnimFrame for the next frame
+    /// Widgets which are still animating have to request again every frame
+    pub fn request_anim_frame(&mut self) {
+        self.env.data.anim_frame = true;
+    }
+    /// Requests an Event::Timer after the given delay
+    /// The Event is send to the StateRoot of the requesting Widget, which has to compare the token
+    pub fn request_timer(&mut self, delay: Duration) -> TimerToken {
+        let token = TimerToken::next();
+        self.env.data.timers.insert(token, self.env.node);
+        self.env.data.timer_requests.push((token, delay));
+        token
+    }
+    /// Cancels the timer, its Event wont be delivered
+    pub fn cancel_timer(&mut self, token: TimerToken) {
+        self.env.data.timers.remove(&token);
+    }
+    /// Runs the future on the executor and commits its output to the state
+    /// The Task gets cancelled if the StateRoot of this Widget is removed
+    pub fn spawn<T, F>(&mut self, state: &CloneState<T>, future: F)
+        where T: Clone + Send + Sync + 'static, F: Future<Output=T> + Send + 'static {
+
+        let task = crate::state::spawn(state, future);
+        let tasks = self.env.data.tasks.entry(self.env.node).or_default();
+        tasks.retain(|task|!task.is_finished());
+        tasks.push(task);
+    }
+    /// Sets the mouse cursor of the window
+    /// Widgets changing the cursor on hover have to reset it to Cursor::Arrow when the pointer leaves.
+    pub fn set_cursor(&mut self, cursor: Cursor) {
+        self.env.data.cursor = Some(cursor);
+    }
+    /// Shows the menu at pos in window coordinates
+    /// Commands of the menu without a handler of the window are send only to the StateRoot of
+    /// this Widget.
+    pub fn show_context_menu(&mut self, menu: MenuDesc, pos: Point) {
+        self.env.data.context_menu = Some((menu, pos, self.env.node));
+    }
+    /// Dispatches the command after the current event, like a menu item or a key binding
+    pub fn submit_command(&mut self, command: Command) {
+        self.env.data.commands.push((command, None));
+    }
+    /// Dispatches the command after the current event, without a handler of the window it is
+    /// send only to the StateRoot of this Widget
+    pub fn submit_local_command(&mut self, command: Command) {
+        self.env.data.commands.push((command, Some(self.env.node)));
+    }
+    /// Returns false while the predicate of the command's handler in the window returns false
+    pub fn is_command_enabled(&self, command: Command) -> bool {
+        self.env.data.registry.is_enabled(command)
+    }
+    /// Returns the size of the window, e.g. to keep popups inside of it
+    pub fn window_size(&self) -> Size {
+        self.env.data.window_size
+    }
+    /// Returns the last position of the mouse in window coordinates
+    pub fn mouse_pos(&self) -> Point {
+        self.env.data.mouse_pos
+    }
+    /// Returns the position of the Widget in window coordinates, pos is the position of the
+    /// current mouse event in the coordinates of the Widget
+    pub fn window_origin(&self, pos: Point) -> Point {
+        self.env.data.mouse_pos - pos.to_vec2()
+    }
+    /// Opens a popup above all Widgets, rect is in window coordinates
+    /// The Widget receives the events of the popup as Event::Overlay. A window shows only one popup,
+    /// the owner of a replaced popup receives Event::OverlayClosed.
+    pub fn open_overlay(&mut self, rect: Rect, painter: OverlayPainter) -> OverlayToken {
+        let token = OverlayToken::next();
+        if let Some(old) = self.env.data.remove_overlay() {
+            self.env.data.closed_overlays.push((old.token, old.owner));
+        }
+        self.env.data.mark_overlay_dirty(rect);
+        self.env.data.overlay = Some(Overlay {
+            token,
+            owner: self.env.node,
+            rect,
+            painter,
+        });
+        token
+    }
+    /// Shows a tooltip above all Widgets and popups, rect is in window coordinates
+    /// The tooltip is moved inside of the window. A window shows only one tooltip, it is hidden
+    /// on a click or a key press. The owner of a tooltip hidden this way or replaced by another one
+    /// receives Event::OverlayClosed.
+    pub fn show_tooltip(&mut self, rect: Rect, painter: OverlayPainter) -> OverlayToken {
+        let bounds = self.env.data.window_size;
+        let origin = Point::new(
+            rect.x0.min(bounds.width - rect.width()).max(0.0),
+            rect.y0.min(bounds.height - rect.height()).max(0.0),
+        );
+        let rect = Rect::from_origin_size(origin, rect.size());
+        let token = OverlayToken::next();
+        self.env.data.close_tooltip();
+        self.env.data.mark_overlay_dirty(rect);
+        self.env.data.tooltip = Some(Overlay {
+            token,
+            owner: self.env.node,
+            rect,
+            painter,
+        });
+        token
+    }
+    pub fn hide_tooltip(&mut self, token: OverlayToken) {
+        if self.env.data.tooltip.as_ref().map_or(false, |tooltip|tooltip.token == token) {
+            self.env.data.remove_tooltip();
+        }
+    }
+    /// Starts dragging the payload, the preview follows the mouse until the button is released
+    /// The drag is cancelled when the mouse leaves the window.
+    /// Drop targets take the payload by accept_drop, afterwards this Widget receives
+    /// Event::DragEnded. Does nothing while another drag is running.
+    pub fn start_drag<P: Any>(&mut self, payload: P, preview_size: Size, preview: OverlayPainter) {
+        if self.env.data.drag.is_some() {
+            return;
+        }
+        let drag = Drag {
+            payload: Some(Box::new(payload)),
+            source: self.env.node,
+            preview_size,
+            preview,
+        };
+        self.env.data.mark_overlay_dirty(drag.preview_rect(self.env.data.mouse_pos));
+        self.env.data.drag = Some(drag);
+        //The drop targets need the mouse events
+        self.env.data.captured = false;
+    }
+    /// Returns true while the mouse is captured
+    /// Layout Widgets send the mouse events to the child, which consumed the MouseDown, until the
+    /// button is released, even outside of its bounds. If it is released outside, the child
+    /// receives MouseExit before MouseUp.
+    pub fn is_pointer_captured(&self) -> bool {
+        self.env.data.captured
+    }
+    /// Ends the capture before the button is released
+    pub fn release_pointer(&mut self) {
+        self.env.data.captured = false;
+    }
+    /// Returns the payload of the running drag if it has the type P
+    pub fn drag_payload<P: Any>(&self) -> Option<&P> {
+        self.env.data.drag.as_ref()
+            .and_then(|drag|drag.payload.as_ref())
+            .and_then(|payload|payload.downcast_ref())
+    }
+    /// Takes the payload of the running drag if it has the type P, call it on Event::MouseUp
+    pub fn accept_drop<P: Any>(&mut self) -> Option<P> {
+        let drag = self.env.data.drag.as_mut()?;
+        if !drag.payload.as_ref()?.is::<P>() {
+            return None;
+        }
+        drag.payload.take()?.downcast().ok().map(|payload|*payload)
+    }
+    /// Replaces the painter of the popup, e.g. after its content changed
+    pub fn update_overlay(&mut self, token: OverlayToken, painter: OverlayPainter) {
+        let rect = match self.env.data.overlay.as_mut() {
+            Some(overlay) if overlay.token == token => {
+                overlay.painter = painter;
+                overlay.rect
+            }
+            _ => return,
+        };
+        self.env.data.mark_overlay_dirty(rect);
+    }
+    /// Closes the popup, its owner wont receive Event::OverlayClosed
+    pub fn close_overlay(&mut self, token: OverlayToken) {
+        if self.env.data.overlay.as_ref().map_or(false, |overlay|overlay.token == token) {
+            self.env.data.remove_overlay();
+        }
+    }
+    /// Inserts the node into the slot of a removed child or into a new slot
     pub(crate) fn insert<'b>(&'b mut self, root: StateRoot) -> (ChildUniq<'b, StateRoot>, Env<'b>, u32) {
-        let index = self.childs.count();
-        let child = self.childs.add_child(root);
+        let childs = &mut self.childs;
+        let free = (0..childs.count()).find(|index|childs.get_child_unique(*index).removed);
+        let (index, child) = match free {
+            Some(index) => {
+                let mut child = self.childs.get_child_unique(index);
+                {
+                    let (this, _) = child.get_both_unique();
+                    *this = root;
+                }
+                self.env.data.free_slots -= 1;
+                (index, child)
+            }
+            None => (self.childs.count(), self.childs.add_child(root)),
+        };
         {
-            let nodes = &mut self.env.dependent_nodes;
+            let nodes = &mut self.env.data.dependent_nodes;
             let state = child.get_ref().states();
             nodes.entry(state).or_default().push(child.index());
+            self.env.data.parents.insert(child.index(), self.env.node);
         }
         (child, self.env.id(), index)
     }
     pub(crate) fn get_child<'b>(&'b mut self, index: u32) -> (ChildUniq<'b, StateRoot>, Env<'b>) {
         (self.childs.get_child_unique(index), self.env.id())
     }
+    /// Removes the child and all of its descendants, see StateRoot::remove_subtree
+    pub(crate) fn remove_child(&mut self, index: u32) {
+        self.childs.get_child_unique(index).remove_subtree(self.env.id());
+    }
+}
+
+/// The number of registrations in a WidgetGraph
+/// Tests can compare the stats before inserting and after removing Widgets to find leaks
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct GraphStats {
+    /// the number of StateRoots without the root
+    pub nodes: usize,
+    /// the number of slots in the pool used by StateRoots without the root, including removed
+    /// ones which are reused by the next insert
+    pub slots: usize,
+    /// the number of nodes registered to States
+    pub dependencies: usize,
+    pub timers: usize,
+    pub tasks: usize,
 }
 
 pub struct WidgetGraph {
     tree: Tree<StateRoot>,
-    dependent_nodes: HashMap<StateID, Vec<u32>>,
+    data: GraphData,
     dirty_rect: Option<Rect>,
     size: Size,
     pref_size: PrefSize,
@@ -163,13 +535,16 @@ impl WidgetGraph {
 
         let mut graph = WidgetGraph {
             tree: Tree::new(StateRoot::new(Box::new(widget))),
-            dependent_nodes: HashMap::new(),
+            data: GraphData::default(),
             dirty_rect: None,
             size: Size::ZERO,
             pref_size: PrefSize::zero(),
             re_layout: true,
         };
 
+        graph.tree.mut_top().inner().build(Env::new(&mut graph.data));
+        graph.lifecycle(&Lifecycle::WidgetAdded);
+
         //focus the first!
         graph.traverse_focus();
 
@@ -179,8 +554,14 @@ impl WidgetGraph {
     pub fn update(&mut self, states: &[StateID]) {
         let mut change = Change::None;
 
+        //Nodes depending on several of the states are updated only once
+        let mut updated = HashSet::new();
+
         for state in states {
-            for node in self.dependent_nodes.get(state).unwrap_or(&Vec::new()) {
+            for node in self.data.dependent_nodes.get(state).unwrap_or(&Vec::new()) {
+                if !updated.insert(*node) {
+                    continue;
+                }
                 if let Some(node) = self.tree.get_index_mut(*node) {
                     change = change.merge(node.update());
                 }
@@ -190,12 +571,147 @@ impl WidgetGraph {
         self.apply_change(change);
     }
     pub fn handle_event(&mut self, event: Event) -> EventResponse {
-
-        let response = self.tree.mut_top().inner().handle_event(event, Env::new(&mut self.dependent_nodes));
-
+        match event {
+            Event::MouseDown(_) | Event::Wheel(_) | Event::KeyDown(_) => self.data.close_tooltip(),
+            _ => {}
+        }
+        //A release outside of the window isn't reported, the drag is cancelled when the mouse
+        //leaves the window or when a MouseDown shows that the button was released
+        let cancelled = match event {
+            Event::MouseExit | Event::MouseDown(_) if self.data.drag.is_some() => self.end_drag(),
+            _ => EventResponse::NONE,
+        };
+        match event {
+            //The captured Widget stays hovered until the button is released
+            Event::MouseExit if self.data.captured => {
+                self.data.exit_pending = true;
+                return EventResponse::NONE;
+            }
+            Event::MouseEnter(_) if self.data.captured => {
+                self.data.exit_pending = false;
+                return EventResponse::NONE;
+            }
+            _ => {}
+        }
+        if let Some(mouse) = event.mouse_event() {
+            self.data.move_mouse(mouse.pos);
+        }
+        let pressed = if let Event::MouseDown(_) = event {true} else {false};
+        let released = if let Event::MouseUp(_) = event {true} else {false};
+        let mut response = match self.handle_overlay_event(&event) {
+            Some(response) => response,
+            None => self.dispatch(event),
+        };
+        if pressed && !response.is_present() && self.data.drag.is_none() {
+            self.data.captured = true;
+        }
+        if released {
+            self.data.captured = false;
+            response = response.merge(self.end_drag());
+            if std::mem::replace(&mut self.data.exit_pending, false) {
+                response = response.merge(self.dispatch(Event::MouseExit));
+            }
+        }
+        cancelled.merge(response)
+    }
+    /// Ends the drag after the drop targets received the MouseUp
+    fn end_drag(&mut self) -> EventResponse {
+        match self.data.drag.take() {
+            Some(drag) => {
+                self.data.mark_overlay_dirty(drag.preview_rect(self.data.mouse_pos));
+                self.deliver(drag.source, Event::DragEnded(drag.payload.is_none()))
+            }
+            None => EventResponse::NONE,
+        }
+    }
+    fn dispatch(&mut self, event: Event) -> EventResponse {
+        let response = self.tree.mut_top().inner().handle_event(event, Env::new(&mut self.data));
         self.apply_change(response.change());
+
+        if let Some(rect) = self.data.overlay_dirty.take() {
+            self.apply_change(Change::Content(rect));
+        }
+        //Notify the owners of popups replaced or closed during the event
+        while let Some((token, owner)) = self.data.closed_overlays.pop() {
+            let _ = self.deliver(owner, Event::OverlayClosed(token));
+        }
+        response
+    }
+    /// Sends the event only to the StateRoot node and its ancestors
+    fn deliver(&mut self, node: u32, event: Event) -> EventResponse {
+        self.data.route = Some(self.data.path(node));
+        let response = self.dispatch(event);
+        self.data.route = None;
         response
     }
+    /// Redirects key events and mouse events inside of the popup to its owner
+    /// A click outside of the popup closes it
+    fn handle_overlay_event(&mut self, event: &Event) -> Option<EventResponse> {
+        let (token, owner, rect) = {
+            let overlay = self.data.overlay.as_ref()?;
+            (overlay.token, overlay.owner, overlay.rect)
+        };
+        let popup_event = match event {
+            Event::KeyDown(_) | Event::KeyUp(_) => event.clone(),
+            _ => {
+                let mouse = event.mouse_event()?;
+                if rect.contains(mouse.pos) {
+                    let mut popup_event = event.clone();
+                    popup_event.shift(rect.origin().to_vec2(), rect.size());
+                    popup_event
+                } else if let Event::MouseDown(_) = event {
+                    self.data.remove_overlay();
+                    return Some(self.deliver(owner, Event::OverlayClosed(token)).merge(EventResponse::DELETED));
+                } else {
+                    return None;
+                }
+            }
+        };
+        Some(self.deliver(owner, Event::Overlay(token, Box::new(popup_event))))
+    }
+    /// Returns true if a Widget requested an animation frame
+    pub fn wants_anim_frame(&self) -> bool {
+        self.data.anim_frame
+    }
+    /// Sends an Event::AnimFrame to all Widgets
+    /// interval is the time since the last frame
+    pub fn anim_frame(&mut self, interval: Duration) {
+        self.data.anim_frame = false;
+        let _ = self.handle_event(Event::AnimFrame(interval));
+    }
+    /// Returns the cursor requested since the last call
+    pub fn take_cursor(&mut self) -> Option<Cursor> {
+        self.data.cursor.take()
+    }
+    /// Returns the context menu requested since the last call with its position and owner
+    pub(crate) fn take_context_menu(&mut self) -> Option<(MenuDesc, Point, u32)> {
+        self.data.context_menu.take()
+    }
+    /// Returns the commands submitted by Widgets since the last call
+    pub(crate) fn take_commands(&mut self) -> Vec<(Command, Option<u32>)> {
+        std::mem::replace(&mut self.data.commands, Vec::new())
+    }
+    /// The commands of the window
+    pub(crate) fn registry(&mut self) -> &mut CommandRegistry {
+        &mut self.data.registry
+    }
+    /// Sends Event::Command to all Widgets or only to the owner StateRoot
+    pub(crate) fn command(&mut self, command: Command, owner: Option<u32>) -> EventResponse {
+        match owner {
+            Some(owner) => self.deliver(owner, Event::Command(command)),
+            None => self.dispatch(Event::Command(command)),
+        }
+    }
+    /// Returns all timers requested since the last call with their delay
+    pub fn take_timer_requests(&mut self) -> Vec<(TimerToken, Duration)> {
+        std::mem::replace(&mut self.data.timer_requests, Vec::new())
+    }
+    /// Delivers the Event::Timer to the StateRoot which requested it
+    pub fn timer(&mut self, token: TimerToken) {
+        if let Some(node) = self.data.timers.remove(&token) {
+            let _ = self.deliver(node, Event::Timer(token));
+        }
+    }
     fn apply_change(&mut self, change: Change) {
         match change {
             Change::None => {}
@@ -207,6 +723,27 @@ impl WidgetGraph {
             }
         }
     }
+    /// Sends the lifecycle notification to all Widgets
+    pub fn lifecycle(&mut self, event: &Lifecycle) {
+        self.tree.mut_top().inner().lifecycle(event, Env::new(&mut self.data));
+    }
+    /// Removes all Widgets, the graph is empty afterwards
+    pub fn clear(&mut self) {
+        self.lifecycle(&Lifecycle::WidgetRemoved);
+        self.tree.mut_top().inner().remove_subtree(Env::new(&mut self.data));
+    }
+    pub fn stats(&self) -> GraphStats {
+        GraphStats {
+            nodes: self.data.parents.len(),
+            slots: self.data.parents.len() + self.data.free_slots,
+            dependencies: self.data.dependent_nodes.values().map(Vec::len).sum(),
+            timers: self.data.timers.len(),
+            tasks: self.data.tasks.values()
+                .flat_map(|tasks|tasks.iter())
+                .filter(|task|!task.is_finished())
+                .count(),
+        }
+    }
     pub fn dirty(&self) -> Option<Rect> {
         self.dirty_rect.clone()
     }
@@ -216,16 +753,17 @@ impl WidgetGraph {
     }
 
     pub fn pref_size(&mut self) -> PrefSize {
-        self.tree.mut_top().inner().get_pref_size(Env::new(&mut self.dependent_nodes))
+        self.tree.mut_top().inner().get_pref_size(Env::new(&mut self.data))
     }
 
     pub fn layout(&mut self, size: Size) {
         if self.size != size || self.re_layout {
             self.size = size;
             self.re_layout = false;
+            self.data.window_size = size;
 
-            self.pref_size = self.tree.mut_top().inner().get_pref_size(Env::new(&mut self.dependent_nodes));
-            self.tree.mut_top().inner().layout(size, Env::new(&mut self.dependent_nodes));
+            self.pref_size = self.tree.mut_top().inner().get_pref_size(Env::new(&mut self.data));
+            self.tree.mut_top().inner().layout(size, Env::new(&mut self.data));
 
             self.dirty_rect = Some(Rect::new(0.0, 0.0, self.size.width, self.size.height));
         }
@@ -239,17 +777,108 @@ impl WidgetGraph {
             self.dirty_rect = None;
         }
 
-        self.tree.mut_top().inner().draw(piet, size, dirty_rect, Env::new(&mut self.dependent_nodes));
+        self.tree.mut_top().inner().draw(piet, size, dirty_rect, Env::new(&mut self.data));
+
+        for overlay in self.data.overlay.iter_mut().chain(self.data.tooltip.iter_mut()) {
+            let rect = overlay.rect;
+            if rect.intersect(dirty_rect).area() > 0.0 {
+                let _ = piet.with_save(|piet|{
+                    piet.clip(rect);
+                    piet.transform(Affine::translate(rect.origin().to_vec2()));
+                    (overlay.painter)(piet, rect.size());
+                    Ok(())
+                });
+            }
+        }
+        if let Some(drag) = self.data.drag.as_mut() {
+            let rect = drag.preview_rect(self.data.mouse_pos);
+            if rect.intersect(dirty_rect).area() > 0.0 {
+                let _ = piet.with_save(|piet|{
+                    piet.clip(rect);
+                    piet.transform(Affine::translate(rect.origin().to_vec2()));
+                    (drag.preview)(piet, rect.size());
+                    Ok(())
+                });
+            }
+        }
         false
     }
     pub fn traverse_focus(&mut self) {
         //Traversing the focus through the Tree
-        let focus = self.tree.mut_top().inner().traverse_focus(Env::new(&mut self.dependent_nodes));
+        let focus = self.tree.mut_top().inner().traverse_focus(Env::new(&mut self.data));
 
         //If returned false the last widget traversed the focus => beginn at the start!
         if !focus {
-            self.tree.mut_top().inner().traverse_focus(Env::new(&mut self.dependent_nodes));
+            self.tree.mut_top().inner().traverse_focus(Env::new(&mut self.data));
         }
         //Dont try again, if the top-widget returns false again, the graph contains no focusable Widgets!
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::{Spacer, StateWrapper, state};
+    use crate::state::key::Key;
+
+    const INSERT: Command = Command("test-insert");
+    const REMOVE: Command = Command("test-remove");
+
+    /// Inserts and removes a StateRoot on command
+    struct Holder(StateWrapper);
+
+    impl Widget<()> for Holder {
+        fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &()) {}
+
+        fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<()>) -> EventResponse {
+            match event {
+                Event::Command(INSERT) => {
+                    self.0 = state(&CloneState::new(0), Spacer::grow());
+                    Widget::<()>::build(&mut self.0, context);
+                }
+                Event::Command(REMOVE) => self.0.remove(context),
+                _ => {}
+            }
+            EventResponse::NONE
+        }
+
+        fn get_pref_size(&mut self, context: WidgetContext, data: &()) -> PrefSize {
+            PrefSize::zero()
+        }
+
+        fn layout(&mut self, size: Size, context: WidgetContext, data: &()) {}
+
+        fn build(&mut self, context: WidgetContext) {}
+
+        fn update(&mut self, new: &(), old: Option<&()>) -> Change {
+            Change::None
+        }
+
+        fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+            Widget::<()>::lifecycle(&mut self.0, event, context)
+        }
+    }
+
+    #[test]
+    fn removed_nodes_are_unregistered() {
+        let mut graph = WidgetGraph::new(Holder(StateWrapper::Removed));
+        let empty = graph.stats();
+
+        graph.command(INSERT, None);
+        assert_eq!(graph.stats().nodes, empty.nodes + 1);
+        assert_eq!(graph.stats().dependencies, empty.dependencies + 1);
+
+        graph.command(REMOVE, None);
+        let removed = graph.stats();
+        assert_eq!(removed, GraphStats { slots: empty.slots + 1, ..empty });
+
+        //The slot is reused instead of growing the pool
+        graph.command(INSERT, None);
+        graph.command(REMOVE, None);
+        assert_eq!(graph.stats(), removed);
+
+        //Removed subtrees and the root aren't counted again
+        graph.clear();
+        assert_eq!(graph.stats(), removed);
+    }
+}
@@ -1,13 +1,18 @@
 use crate::app::widget_graph::WidgetGraph;
-use druid_shell::{WindowHandle, WinHandler, IdleToken, MouseEvent, KeyEvent, KeyCode, Application};
-use crate::state::{StateID, register_listener};
+use druid_shell::{WindowHandle, WinHandler, IdleToken, MouseEvent, KeyEvent, Application, TimerToken};
+use crate::state::{register_listener, unregister_listener, Listener, Handle, CloneState, State};
 use druid_shell::kurbo::{Size, Rect};
-use std::sync::mpsc::Receiver;
 use crate::widgets::Widget;
-use crate::app::event::Event;
+use crate::app::event::{Event, Lifecycle};
 use crate::app::size::PrefSize;
 use druid_shell::piet::Piet;
 use std::any::Any;
+use std::time::{Instant, Duration};
+use std::collections::HashMap;
+use crate::app::timer;
+use crate::app::menu::{MenuDesc, MenuCommands};
+use crate::app::command::{Command, CommandDesc, Keymap};
+use crate::state::StateID;
 
 struct Window{
     widgets: WidgetGraph,
@@ -15,8 +20,19 @@ struct Window{
     mouse_focus: bool,
     min_size: MinSize,
     title: String,
-    change_queue: Option<Receiver<StateID>>,
+    listener: Option<Listener>,
     size: Size,
+    last_frame: Option<Instant>,
+    timers: HashMap<TimerToken, timer::TimerToken>,
+    geometry: Option<Handle<WindowGeometry>>,
+    menu: Option<MenuDesc>,
+    /// the States of the enabled and checked flags of the menu bar
+    menu_states: Vec<StateID>,
+    bar_commands: MenuCommands,
+    popup_commands: MenuCommands,
+    /// the node which showed the last context menu
+    popup_owner: Option<u32>,
+    keymap: CloneState<Keymap>,
 }
 
 impl Window {
@@ -27,25 +43,82 @@ impl Window {
             mouse_focus: false,
             min_size,
             title,
-            change_queue: None,
-            size
+            listener: None,
+            size,
+            last_frame: None,
+            timers: HashMap::new(),
+            geometry: None,
+            menu: None,
+            menu_states: Vec::new(),
+            bar_commands: MenuCommands::new(0),
+            popup_commands: MenuCommands::new(1 << 31),
+            popup_owner: None,
+            keymap: CloneState::new(Keymap::default()),
         }
     }
 
     fn update_states(&mut self) {
-        if let Some(changes) = self.change_queue.as_ref() {
-            let changes= changes.try_iter().collect::<Vec<_>>();
+        if let Some(listener) = self.listener.as_ref() {
+            let changes = listener.changes();
+            if changes.iter().any(|state|self.menu_states.contains(state)) {
+                self.update_menu();
+            }
             self.widgets.update(&changes);
         }
         self.update_widgets();
     }
 
+    /// Rebuilds the menu bar after one of its States changed
+    fn update_menu(&mut self) {
+        if let (Some(menu), Some(handle)) = (self.menu.as_ref(), self.window_handle.as_ref()) {
+            self.bar_commands.clear();
+            handle.set_menu(menu.build(false, &mut self.bar_commands));
+        }
+    }
+
+    /// Calls the handler of the command or sends it to the Widgets, returns false if the command
+    /// is disabled
+    fn dispatch_command(&mut self, command: Command, owner: Option<u32>) -> bool {
+        if !self.widgets.registry().is_enabled(command) {
+            return false;
+        }
+        if !self.widgets.registry().execute(command) {
+            if command == Command::FOCUS_NEXT {
+                self.widgets.traverse_focus();
+            } else {
+                let _ = self.widgets.command(command, owner);
+            }
+        }
+        self.update_states();
+        true
+    }
+
     fn update_widgets(&mut self) {
+        for (command, owner) in self.widgets.take_commands() {
+            self.dispatch_command(command, owner);
+        }
         self.widgets.layout(self.size);
 
         if let Some((rect, handle)) = self.widgets.dirty().zip(self.window_handle.as_ref()) {
             handle.invalidate_rect(rect);
         }
+        if let Some(handle) = self.window_handle.as_mut() {
+            if let Some(cursor) = self.widgets.take_cursor() {
+                handle.set_cursor(&cursor);
+            }
+            if let Some((menu, pos, owner)) = self.widgets.take_context_menu() {
+                self.popup_commands.clear();
+                self.popup_owner = Some(owner);
+                handle.show_context_menu(menu.build(true, &mut self.popup_commands), pos);
+            }
+            if self.widgets.wants_anim_frame() {
+                handle.request_anim_frame();
+            }
+            for (token, delay) in self.widgets.take_timer_requests() {
+                let shell_token = handle.request_timer(Instant::now() + delay);
+                self.timers.insert(shell_token, token);
+            }
+        }
     }
 
     fn handle_event(&mut self, event: Event) -> bool {
@@ -67,14 +140,15 @@ impl WinHandler for Window {
         {
             let handle = handle.clone();
 
-            let (listener, id) = register_listener(Some(Box::new(move|| {
+            let listener = register_listener(Some(Box::new(move|| {
                 handle.get_idle_handle().unwrap().schedule_idle(IdleToken::new(0))
             })));
-            self.change_queue = Some(listener);
+            self.listener = Some(listener);
 
         }
 
         println!("opened window '{}'", self.title);
+        self.widgets.lifecycle(&Lifecycle::Shown);
         self.update_states();
     }
 
@@ -82,11 +156,31 @@ impl WinHandler for Window {
         let old_size = self.size;
         self.size = size;
 
+        if let Some(geometry) = self.geometry.as_ref() {
+            geometry.set(WindowGeometry::from_size(size));
+        }
+
         self.widgets.layout(size);
     }
 
     fn paint(&mut self, piet: &mut Piet, invalid_rect: Rect) -> bool {
-        self.widgets.draw_widgets(piet, self.size, invalid_rect)
+        if self.widgets.wants_anim_frame() {
+            let now = Instant::now();
+            let interval = self.last_frame.map_or(Duration::from_secs(0), |last|now - last);
+            self.last_frame = Some(now);
+
+            self.widgets.anim_frame(interval);
+            self.update_states();
+        }
+
+        self.widgets.draw_widgets(piet, self.size, invalid_rect);
+
+        //Returning true requests the next animation frame
+        let animating = self.widgets.wants_anim_frame();
+        if !animating {
+            self.last_frame = None;
+        }
+        animating
     }
 
     fn mouse_move(&mut self, event: &MouseEvent) {
@@ -113,6 +207,10 @@ impl WinHandler for Window {
         self.handle_event(Event::MouseUp(event.clone()));
     }
 
+    fn wheel(&mut self, event: &MouseEvent) {
+        self.handle_event(Event::Wheel(event.clone()));
+    }
+
     fn mouse_leave(&mut self) {
         if self.mouse_focus {
             self.handle_event(Event::MouseExit);
@@ -121,11 +219,11 @@ impl WinHandler for Window {
     }
 
     fn key_down(&mut self, event: KeyEvent) -> bool {
-        //TODO: change, this is only temporary
-        if event.key_code == KeyCode::Tab {
-            self.widgets.traverse_focus();
-            self.update_states();
-            return true;
+        //The bindings of the Window take precedence, disabled commands leave the key to the Widgets
+        if let Some(command) = self.keymap.with_fetched_value(|keymap, _|keymap.command(&event)) {
+            if self.dispatch_command(command, None) {
+                return true;
+            }
         }
         self.handle_event(Event::KeyDown(event.clone()))
     }
@@ -134,6 +232,31 @@ impl WinHandler for Window {
         self.handle_event(Event::KeyUp(event.clone()));
     }
 
+    fn command(&mut self, id: u32) {
+        if let Some(command) = self.bar_commands.get(id) {
+            let _ = self.dispatch_command(command, None);
+        } else if let Some(command) = self.popup_commands.get(id) {
+            let owner = self.popup_owner;
+            let _ = self.dispatch_command(command, owner);
+        }
+    }
+
+    fn timer(&mut self, token: TimerToken) {
+        if let Some(token) = self.timers.remove(&token) {
+            self.widgets.timer(token);
+            self.update_states();
+        }
+    }
+
+    fn destroy(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            unregister_listener(listener.id());
+        }
+        self.widgets.clear();
+        self.timers.clear();
+        println!("closed window '{}'", self.title);
+    }
+
     fn idle(&mut self, token: IdleToken) {
         self.update_states()
     }
@@ -144,6 +267,26 @@ impl WinHandler for Window {
 
 }
 
+/// The size of a Window, which can be saved by a Store
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WindowGeometry {
+    pub fn from_size(size: Size) -> Self {
+        WindowGeometry {
+            width: size.width,
+            height: size.height,
+        }
+    }
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum MinSize {
     Fixed(Size),
@@ -154,6 +297,10 @@ pub struct WindowBuilder {
     size: Size,
     title: String,
     min_size: MinSize,
+    geometry: Option<CloneState<WindowGeometry>>,
+    menu: Option<MenuDesc>,
+    commands: Vec<CommandDesc>,
+    keymap: Option<CloneState<Keymap>>,
 }
 
 impl WindowBuilder {
@@ -162,6 +309,10 @@ impl WindowBuilder {
             size: Size::new(640.0, 480.0),
             title: "App".to_string(),
             min_size: MinSize::Fixed(Size::ZERO),
+            geometry: None,
+            menu: None,
+            commands: Vec::new(),
+            keymap: None,
         }
     }
     pub fn title(mut self, title: impl Into<String>) -> Self {
@@ -176,6 +327,37 @@ impl WindowBuilder {
         self.min_size = MinSize::Fixed(size);
         self
     }
+    /// Restores the size of the Window from the Store and saves it on resize
+    /// The position can't be restored yet, since druid-shell doesn't expose it
+    #[cfg(feature = "persist")]
+    pub fn persist_geometry(mut self, store: &crate::state::Store, key: impl Into<String>) -> Self {
+        let geometry = CloneState::new(WindowGeometry::from_size(self.size));
+        //An unreadable geometry falls back to the default size and is overwritten on resize
+        let _ = store.track(key, &geometry);
+        self.geometry = Some(geometry);
+        self
+    }
+
+    /// Sets the menu bar of the Window
+    pub fn menu(mut self, menu: MenuDesc) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+    /// Handles the command when it is dispatched by a menu, a key binding or a Widget
+    /// Commands without a handler are send to the Widgets as Event::Command.
+    pub fn on_command(self, command: Command, handler: impl FnMut() + 'static) -> Self {
+        self.command(CommandDesc::new(command, handler))
+    }
+    pub fn command(mut self, command: CommandDesc) -> Self {
+        self.commands.push(command);
+        self
+    }
+    /// The key bindings of the Window, they can be changed while the Window is open
+    /// Without a Keymap the Window uses Keymap::default().
+    pub fn keymap(mut self, keymap: &CloneState<Keymap>) -> Self {
+        self.keymap = Some(keymap.clone());
+        self
+    }
 
     pub fn open<W: Widget<()> + 'static>(self, widget: W) {
         println!("create window '{}'", &self.title);
@@ -188,6 +370,13 @@ impl WindowBuilder {
                                       widget,
                                       self.min_size.clone(),
                                       self.title.clone());
+        handler.geometry = self.geometry.as_ref().map(CloneState::handle);
+        for command in self.commands {
+            handler.widgets.registry().insert(command);
+        }
+        if let Some(keymap) = self.keymap.as_ref() {
+            handler.keymap = keymap.clone();
+        }
 
         let pref_size = handler.widgets_pref_size();
 
@@ -202,13 +391,42 @@ impl WindowBuilder {
         //Create Platform Window
 
         let mut window = druid_shell::WindowBuilder::new(app.clone());
-        window.set_size(Size::new(pref_size.max.width.max(200.0), pref_size.max.width.max(150.0)));
+        if let Some(geometry) = self.geometry.as_ref() {
+            let handle = geometry.handle();
+            window.set_size(handle.get().map_or(self.size, |geometry|geometry.size()));
+        } else {
+            window.set_size(Size::new(pref_size.max.width.max(200.0), pref_size.max.width.max(150.0)));
+        }
         window.resizable(true);
         window.set_title(self.title);
+        if let Some(menu) = self.menu {
+            handler.menu_states = menu.states();
+            window.set_menu(menu.build(false, &mut handler.bar_commands));
+            handler.menu = Some(menu);
+        }
         window.set_min_size(Size::new(min_size.width.max(150.0), min_size.height.max(50.0)));
         window.set_handler(Box::new(handler));
         window.build().unwrap();
 
         app.run(None)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::Spacer;
+    use crate::state::listener_count;
+
+    #[test]
+    fn destroy_unregisters_listener() {
+        let count = listener_count();
+        let mut window = Window::new(Size::new(100.0, 100.0), Spacer::grow(), MinSize::Fixed(Size::ZERO), "test".to_string());
+        window.listener = Some(register_listener(None));
+        assert_eq!(listener_count(), count + 1);
+
+        window.destroy();
+        assert_eq!(listener_count(), count);
+        assert_eq!(window.widgets.stats().nodes, 0);
+    }
+}
@@ -11,10 +11,12 @@ pub mod app;
 
 pub mod prelude{
     pub use crate::widgets::*;
-    pub use crate::widgets::layout::{Container, VBox, HBox, Spacing};
-    pub use crate::widgets::text::{Label, DynLabel, TextInput};
-    pub use crate::state::{key::Key, CloneState, State};
+    pub use crate::widgets::layout::{Container, VBox, HBox, Spacing, Split};
+    pub use crate::widgets::text::{Label, DynLabel, TextInput, NumberInput};
+    pub use crate::state::{key::Key, CloneState, Derived, State};
     pub use crate::app::window::WindowBuilder;
+    pub use crate::app::menu::{MenuDesc, MenuItem};
+    pub use crate::app::command::{Command, CommandDesc, Keymap, Shortcut};
     pub use crate::app::event::*;
     pub use crate::app::size::PrefSize;
     pub use ::druid_shell::piet::Color;
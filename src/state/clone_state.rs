@@ -1,4 +1,4 @@
-use crate::state::{Handle, HandleInner, StateInner, StateID, State};
+use crate::state::{Handle, StateInner, StateID, State, record};
 use std::sync::Arc;
 use crate::state::key::Key;
 
@@ -19,8 +19,9 @@ impl<T: 'static + Clone + Send + Sync> CloneState<T> {
         state
     }
 
-    pub fn handle(&self) -> Handle {
-        Handle(self.inner.clone() as Arc<dyn HandleInner + Send + Sync>)
+    /// Returns a Handle to update the State from other threads
+    pub fn handle(&self) -> Handle<T> {
+        Handle::new(&self.inner)
     }
 }
 
@@ -29,11 +30,14 @@ impl<T: 'static + Clone + Send + Sync> State<T> for CloneState<T> {
         self.inner.id()
     }
 
+    //Every read is recorded, the cached value is read as well as a fetched one
     fn with_value<R>(&self, operation: impl FnOnce(&T) -> R) -> R {
+        record(self.inner.id());
         operation(&self.cache)
     }
 
     fn with_fetched_value<R>(&mut self, operation: impl FnOnce(&T, Option<&T>) -> R) -> R {
+        record(self.inner.id());
         let new_commit = self.inner.commit();
         if new_commit > self.commit {
             self.commit = new_commit;
@@ -45,6 +49,7 @@ impl<T: 'static + Clone + Send + Sync> State<T> for CloneState<T> {
     }
 
     fn with_key<R>(&mut self, operation: impl FnOnce(Key<T>) -> R) -> R {
+        record(self.inner.id());
         let new_commit = self.inner.commit();
         if new_commit > self.commit {
             self.commit = new_commit;
@@ -63,4 +68,18 @@ impl<T: 'static + Clone + Send + Sync> State<T> for CloneState<T> {
 
         r
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde")]
+impl<T: Clone + serde::Serialize> serde::Serialize for CloneState<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.use_value(|value|value.serialize(serializer))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: 'static + Clone + Send + Sync + serde::Deserialize<'de>> serde::Deserialize<'de> for CloneState<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(CloneState::new)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::state::{StateID, State, Dependent, track, record, add_dependency, remove_dependency, update};
+use crate::state::key::Key;
+use std::sync::{Arc, RwLock, Mutex, Weak};
+use std::sync::atomic::{AtomicU64, AtomicBool, Ordering};
+
+struct DerivedInner<T> {
+    id: StateID,
+    compute: Box<dyn Fn() -> T + Send + Sync>,
+    value: RwLock<T>,
+    commit: AtomicU64,
+    dirty: AtomicBool,
+    sources: Mutex<Vec<StateID>>,
+}
+
+impl<T: Send + Sync> Dependent for DerivedInner<T> {
+    fn id(&self) -> StateID {
+        self.id
+    }
+
+    fn invalidate(&self) -> bool {
+        !self.dirty.swap(true, Ordering::SeqCst)
+    }
+}
+
+/// Registers the Derived as dependent of the sources, replacing the old sources
+fn set_sources<T: Send + Sync + 'static>(inner: &Arc<DerivedInner<T>>, sources: Vec<StateID>) {
+    let mut old = inner.sources.lock().unwrap();
+    for source in old.iter() {
+        remove_dependency(*source, inner.id);
+    }
+    let weak: Weak<dyn Dependent> = Arc::downgrade(inner);
+    for source in sources.iter() {
+        add_dependency(*source, weak.clone());
+    }
+    *old = sources;
+}
+
+/// Recomputes the value if a source changed, returns the current commit
+fn refresh<T: Send + Sync + 'static>(inner: &Arc<DerivedInner<T>>) -> u64 {
+    if inner.dirty.swap(false, Ordering::SeqCst) {
+        let (value, sources) = track(||(inner.compute)());
+        *inner.value.write().unwrap() = value;
+        set_sources(inner, sources);
+        inner.commit.fetch_add(1, Ordering::SeqCst);
+    }
+    inner.commit.load(Ordering::SeqCst)
+}
+
+/// A read-only State computed from other States
+///
+/// The sources are tracked automatically: every State read through a Handle or another Derived
+/// while computing the value becomes a source. If a source commits, the Derived notifies its
+/// listeners under its own StateID and recomputes the value the next time it is fetched.
+///
+/// A Derived is read-only: Widgets bound to it must not change the value through their Key,
+/// debug builds panic on such a change, release builds discard it.
+#[derive(Clone)]
+pub struct Derived<T: Clone> {
+    cache: T,
+    commit: u64,
+    inner: Arc<DerivedInner<T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Derived<T> {
+    pub fn new(compute: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        let (value, sources) = track(&compute);
+
+        let inner = Arc::new(DerivedInner {
+            id: StateID::new(),
+            compute: Box::new(compute),
+            value: RwLock::new(value.clone()),
+            commit: AtomicU64::new(0),
+            dirty: AtomicBool::new(false),
+            sources: Mutex::new(Vec::new()),
+        });
+        set_sources(&inner, sources);
+
+        Derived {
+            cache: value,
+            commit: 0,
+            inner,
+        }
+    }
+
+    /// Returns the current value, computing it if a source changed
+    /// Reading a Derived inside the computation of another one makes it a source
+    pub fn get(&self) -> T {
+        record(self.inner.id);
+        refresh(&self.inner);
+        self.inner.value.read().unwrap().clone()
+    }
+
+    /// Recomputes the value even if no source changed and notifies the listeners
+    pub fn invalidate(&self) {
+        if self.inner.invalidate() {
+            update(self.inner.id);
+        }
+    }
+
+    fn fetch(&mut self) {
+        let commit = refresh(&self.inner);
+        if commit > self.commit {
+            self.commit = commit;
+            self.cache.clone_from(&self.inner.value.read().unwrap());
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> State<T> for Derived<T> {
+    fn get_id(&self) -> StateID {
+        self.inner.id
+    }
+
+    fn with_value<R>(&self, operation: impl FnOnce(&T) -> R) -> R {
+        record(self.inner.id);
+        operation(&self.cache)
+    }
+
+    fn with_fetched_value<R>(&mut self, operation: impl FnOnce(&T, Option<&T>) -> R) -> R {
+        record(self.inner.id);
+        self.fetch();
+        operation(&self.cache, None)
+    }
+
+    fn with_key<R>(&mut self, operation: impl FnOnce(Key<T>) -> R) -> R {
+        record(self.inner.id);
+        self.fetch();
+        let mut value = self.cache.clone();
+        let mut change = false;
+        let r = operation(Key::new(&mut value, &mut change));
+        debug_assert!(!change, "the value of a Derived was changed through its Key");
+        r
+    }
+}
@@ -2,35 +2,99 @@ use crate::state::StateID;
 use once_cell::sync::Lazy;
 use druid_shell::Counter;
 use std::sync::mpsc::{Receiver, SyncSender, sync_channel, Sender, channel};
+use std::sync::{Mutex, RwLock, RwLockReadGuard, Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashSet, HashMap};
+use std::cell::{Cell, RefCell};
+use std::sync::Weak;
 use std::thread::spawn;
+use std::mem::replace;
 
 enum Action{
-    StateUpdate(StateID),
-    RegisterListener(Option<Box<dyn Fn() + Send>>, Sender<StateID>, ListenerID),
+    Flush,
+    RegisterListener(ListenerData),
     UnregisterListener(ListenerID),
 }
 
+struct ListenerData {
+    notify: Option<Box<dyn Fn() + Send>>,
+    sender: Sender<StateID>,
+    woken: Arc<AtomicBool>,
+    id: ListenerID,
+}
+
+/// The States which committed since the last flush
+struct Pending {
+    states: Vec<StateID>,
+    contained: HashSet<StateID>,
+    scheduled: bool,
+}
+
+static PENDING: Lazy<Mutex<Pending>> = Lazy::new(||{
+    Mutex::new(Pending {
+        states: Vec::new(),
+        contained: HashSet::new(),
+        scheduled: false,
+    })
+});
+
+/// The IDs of all registered listeners
+static LISTENER_IDS: Lazy<Mutex<HashSet<u64>>> = Lazy::new(||Mutex::new(HashSet::new()));
+
+/// Open transactions hold a read lock, a flush waits for all of them to finish
+static TRANSACTIONS: Lazy<RwLock<()>> = Lazy::new(||RwLock::new(()));
+
+thread_local! {
+    static TRANSACTION_DEPTH: Cell<u32> = Cell::new(0);
+    /// The States read by the currently tracked operation
+    static TRACKER: RefCell<Option<Vec<StateID>>> = RefCell::new(None);
+}
+
+/// A State computed from other States, which has to be invalidated when one of them commits
+pub(crate) trait Dependent: Send + Sync {
+    fn id(&self) -> StateID;
+    /// Marks the value as outdated, returns false if it was already outdated
+    fn invalidate(&self) -> bool;
+}
+
+static DEPENDENTS: Lazy<Mutex<HashMap<StateID, Vec<Weak<dyn Dependent>>>>> = Lazy::new(||{
+    Mutex::new(HashMap::new())
+});
+
 static LISTENER: Lazy<SyncSender<Action>> = Lazy::new(||{
     let (sender, reciever) = sync_channel::<Action>(100);
 
     spawn(move||{
-        let mut data = Vec::<(Option<Box<dyn Fn() + Send>>, Sender<StateID>, ListenerID)>::new();
+        let mut data = Vec::<ListenerData>::new();
 
         for event in reciever.iter() {
             match event {
-                Action::StateUpdate(state_id) => {
-                    for (notify, states, _) in data.iter() {
-                        states.send(state_id).unwrap();
-                        if let Some(notify) = notify {
-                            notify();
+                Action::Flush => {
+                    let states = {
+                        let _barrier = TRANSACTIONS.write().unwrap();
+                        let mut pending = PENDING.lock().unwrap();
+                        pending.scheduled = false;
+                        pending.contained.clear();
+                        replace(&mut pending.states, Vec::new())
+                    };
+
+                    for listener in data.iter() {
+                        for state_id in states.iter() {
+                            let _ = listener.sender.send(*state_id);
+                        }
+                        //Wake every listener only once until it fetched its changes
+                        if !listener.woken.swap(true, Ordering::SeqCst) {
+                            if let Some(notify) = &listener.notify {
+                                notify();
+                            }
                         }
                     }
                 }
-                Action::RegisterListener(observer, listener, id) => {
-                    data.push((observer, listener, id));
+                Action::RegisterListener(listener) => {
+                    data.push(listener);
                 }
                 Action::UnregisterListener(listener) => {
-                    data.retain(|(_, _, id)|id.0 != listener.0);
+                    data.retain(|data|data.id.0 != listener.0);
                 }
             }
         }
@@ -47,17 +111,159 @@ fn next_id() -> ListenerID {
     ListenerID(COUNTER.next())
 }
 
-pub fn register_listener(observer: Option<Box<dyn Fn() + Send>>) -> (Receiver<StateID>, ListenerID) {
+/// Receives the IDs of all States which committed
+pub struct Listener {
+    receiver: Receiver<StateID>,
+    woken: Arc<AtomicBool>,
+    id: ListenerID,
+}
+
+impl Listener {
+    pub fn id(&self) -> ListenerID {
+        self.id
+    }
+    /// Returns the States which committed since the last call, every State only once
+    /// The notify-callback will be called again after the next commit
+    pub fn changes(&self) -> Vec<StateID> {
+        self.woken.store(false, Ordering::SeqCst);
+
+        let mut contained = HashSet::new();
+        self.receiver.try_iter()
+            .filter(|state|contained.insert(*state))
+            .collect()
+    }
+}
+
+pub fn register_listener(observer: Option<Box<dyn Fn() + Send>>) -> Listener {
     let (sender, reciever) = channel();
     let id = next_id();
-    LISTENER.send(Action::RegisterListener(observer, sender, id)).unwrap();
-    (reciever, id)
+    let woken = Arc::new(AtomicBool::new(false));
+    LISTENER_IDS.lock().unwrap().insert(id.0);
+    LISTENER.send(Action::RegisterListener(ListenerData {
+        notify: observer,
+        sender,
+        woken: woken.clone(),
+        id,
+    })).unwrap();
+    Listener {
+        receiver: reciever,
+        woken,
+        id,
+    }
 }
 
 pub fn unregister_listener(id: ListenerID) {
+    LISTENER_IDS.lock().unwrap().remove(&id.0);
     LISTENER.send(Action::UnregisterListener(id)).unwrap();
 }
 
+/// Returns the number of registered listeners, tests can use this to find leaked windows
+pub fn listener_count() -> usize {
+    LISTENER_IDS.lock().unwrap().len()
+}
+
+/// Executes operation as a transaction
+/// All commits inside the operation are send to the listeners together with a single notification.
+/// Nested transactions are part of the outer one.
+pub fn transaction<R>(operation: impl FnOnce() -> R) -> R {
+    let outer = TRANSACTION_DEPTH.with(|depth|{
+        depth.set(depth.get() + 1);
+        depth.get() == 1
+    });
+
+    let _guard = TransactionGuard {
+        barrier: if outer {
+            Some(TRANSACTIONS.read().unwrap())
+        } else {
+            None
+        },
+    };
+
+    operation()
+}
+
+/// Ends the transaction, even if the operation panics
+struct TransactionGuard {
+    barrier: Option<RwLockReadGuard<'static, ()>>,
+}
+
+impl Drop for TransactionGuard {
+    fn drop(&mut self) {
+        drop(self.barrier.take());
+        TRANSACTION_DEPTH.with(|depth|depth.set(depth.get() - 1));
+    }
+}
+
+/// Executes operation and returns the IDs of all States it read
+pub(crate) fn track<R>(operation: impl FnOnce() -> R) -> (R, Vec<StateID>) {
+    let guard = TrackGuard(TRACKER.with(|tracker|tracker.replace(Some(Vec::new()))));
+    let r = operation();
+    let read = TRACKER.with(|tracker|tracker.borrow_mut().take()).unwrap_or_default();
+    drop(guard);
+    (r, read)
+}
+
+/// Restores the tracker of the enclosing operation, even if the operation panics
+struct TrackGuard(Option<Vec<StateID>>);
+
+impl Drop for TrackGuard {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        TRACKER.with(|tracker|tracker.replace(outer));
+    }
+}
+
+/// Records a read of the State for the current tracked operation
+pub(crate) fn record(state: StateID) {
+    TRACKER.with(|tracker|{
+        if let Some(read) = tracker.borrow_mut().as_mut() {
+            if !read.contains(&state) {
+                read.push(state);
+            }
+        }
+    });
+}
+
+pub(crate) fn add_dependency(source: StateID, dependent: Weak<dyn Dependent>) {
+    DEPENDENTS.lock().unwrap().entry(source).or_default().push(dependent);
+}
+
+pub(crate) fn remove_dependency(source: StateID, dependent: StateID) {
+    let mut dependents = DEPENDENTS.lock().unwrap();
+    if let Some(list) = dependents.get_mut(&source) {
+        list.retain(|weak|weak.upgrade().map_or(false, |other|other.id() != dependent));
+        if list.is_empty() {
+            dependents.remove(&source);
+        }
+    }
+}
+
+fn dependents(source: StateID) -> Vec<Arc<dyn Dependent>> {
+    let mut dependents = DEPENDENTS.lock().unwrap();
+    if let Some(list) = dependents.get_mut(&source) {
+        list.retain(|weak|weak.upgrade().is_some());
+        list.iter().filter_map(Weak::upgrade).collect()
+    } else {
+        Vec::new()
+    }
+}
+
 pub(crate) fn update(state: StateID) {
-    LISTENER.send(Action::StateUpdate(state)).unwrap();
-}
\ No newline at end of file
+    let schedule = {
+        let mut pending = PENDING.lock().unwrap();
+        if pending.contained.insert(state) {
+            pending.states.push(state);
+        }
+        !replace(&mut pending.scheduled, true)
+    };
+    //Only the first commit after a flush schedules the next one
+    if schedule {
+        LISTENER.send(Action::Flush).unwrap();
+    }
+
+    for dependent in dependents(state) {
+        if dependent.invalidate() {
+            update(dependent.id());
+        }
+    }
+}
@@ -6,9 +6,17 @@ mod clone_state;
 mod sync_state;
 mod state_inner;
 mod state;
+mod task;
+mod derived;
+#[cfg(feature = "persist")]
+mod persist;
 
-pub use manager::{register_listener, unregister_listener};
-pub(crate) use manager::update;
+pub use manager::{register_listener, unregister_listener, listener_count, transaction, Listener, ListenerID};
+pub(crate) use manager::{update, record, track, add_dependency, remove_dependency, Dependent};
 pub use clone_state::CloneState;
-pub use state_inner::{StateInner, HandleInner, Handle};
-pub use state::{StateID, State};
\ No newline at end of file
+pub use state_inner::{StateInner, Handle, Batch};
+pub use state::{StateID, State};
+pub use task::{spawn, spawn_loadable, Task, Loadable};
+pub use derived::Derived;
+#[cfg(feature = "persist")]
+pub use persist::{Store, StoreBuilder, Format, Migration, ErrorHandler};
@@ -0,0 +1,267 @@
+use crate::state::{CloneState, StateID, Handle, register_listener, unregister_listener};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, Map};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Sender, RecvTimeoutError};
+use std::time::Duration;
+use std::thread::spawn;
+use std::fs;
+use std::io;
+
+/// The file format of a Store
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Format {
+    Json,
+    Ron,
+}
+
+/// Upgrades the stored states from an older version of the format
+pub type Migration = Box<dyn FnOnce(u32, &mut Map<String, Value>)>;
+
+/// Is called with the errors of saves on commit or on drop, which have no caller to return them to
+pub type ErrorHandler = Box<dyn Fn(io::Error) + Send + Sync>;
+
+struct Tracked {
+    key: String,
+    id: StateID,
+    snapshot: Box<dyn Fn() -> Option<Value> + Send>,
+}
+
+struct StoreInner {
+    path: PathBuf,
+    format: Format,
+    version: u32,
+    /// the last saved values, including the ones of untracked keys
+    values: Mutex<Map<String, Value>>,
+    tracked: Mutex<Vec<Tracked>>,
+    on_error: Option<ErrorHandler>,
+}
+
+impl StoreInner {
+    fn report(&self, result: io::Result<()>) {
+        if let (Err(error), Some(on_error)) = (result, self.on_error.as_ref()) {
+            on_error(error);
+        }
+    }
+
+    fn tracks(&self, state: StateID) -> bool {
+        self.tracked.lock().unwrap().iter().any(|tracked|tracked.id == state)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let mut values = self.values.lock().unwrap();
+        for tracked in self.tracked.lock().unwrap().iter() {
+            if let Some(value) = (tracked.snapshot)() {
+                values.insert(tracked.key.clone(), value);
+            }
+        }
+
+        let mut file = Map::new();
+        file.insert("version".to_string(), Value::from(self.version));
+        file.insert("states".to_string(), Value::Object(values.clone()));
+        let file = Value::Object(file);
+
+        let text = match self.format {
+            Format::Json => serde_json::to_string_pretty(&file)
+                .map_err(|error|io::Error::new(io::ErrorKind::InvalidData, error))?,
+            Format::Ron => ron::ser::to_string_pretty(&file, Default::default())
+                .map_err(|error|io::Error::new(io::ErrorKind::InvalidData, error))?,
+        };
+
+        //Write to a temporary file first, so a crash wont leave a half written file behind
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, text)?;
+        fs::rename(&temp, &self.path)
+    }
+}
+
+fn load(path: &PathBuf, format: Format) -> io::Result<Option<Value>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let value = match format {
+        Format::Json => serde_json::from_str(&text)
+            .map_err(|error|io::Error::new(io::ErrorKind::InvalidData, error))?,
+        Format::Ron => ron::de::from_str(&text)
+            .map_err(|error|io::Error::new(io::ErrorKind::InvalidData, error))?,
+    };
+    Ok(Some(value))
+}
+
+enum Message {
+    Changed,
+    Stop,
+}
+
+pub struct StoreBuilder {
+    path: PathBuf,
+    format: Format,
+    version: u32,
+    debounce: Duration,
+    migration: Option<Migration>,
+    on_error: Option<ErrorHandler>,
+}
+
+impl StoreBuilder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        StoreBuilder {
+            path: path.into(),
+            format: Format::Json,
+            version: 0,
+            debounce: Duration::from_millis(500),
+            migration: None,
+            on_error: None,
+        }
+    }
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+    /// The version of the stored format
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+    /// The time to wait after a commit for further commits before saving
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+    /// Is called with the version of the file and its states, if it is older than the version
+    /// of the Store
+    pub fn migration(mut self, migration: impl FnOnce(u32, &mut Map<String, Value>) + 'static) -> Self {
+        self.migration = Some(Box::new(migration));
+        self
+    }
+    /// Is called if saving after a commit or on drop fails, by default these errors are ignored
+    pub fn on_error(mut self, handler: impl Fn(io::Error) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Loads the file and starts saving the tracked states on commit
+    /// A missing file results in an empty Store. A file with a newer version than the one of the
+    /// Store is rejected with ErrorKind::InvalidData, instead of overwriting it with the older
+    /// format.
+    pub fn open(self) -> io::Result<Store> {
+        let mut values = Map::new();
+        if let Some(Value::Object(mut file)) = load(&self.path, self.format)? {
+            let version = file.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+            if version > self.version {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("{} has version {}, newer than version {} of the Store",
+                                                  self.path.display(), version, self.version)));
+            }
+            if let Some(Value::Object(states)) = file.remove("states") {
+                values = states;
+            }
+            if version < self.version {
+                if let Some(migration) = self.migration {
+                    migration(version, &mut values);
+                }
+            }
+        }
+
+        let inner = Arc::new(StoreInner {
+            path: self.path,
+            format: self.format,
+            version: self.version,
+            values: Mutex::new(values),
+            tracked: Mutex::new(Vec::new()),
+            on_error: self.on_error,
+        });
+
+        let (sender, receiver) = channel();
+        let notify = Mutex::new(sender.clone());
+        let listener = register_listener(Some(Box::new(move||{
+            let _ = notify.lock().unwrap().send(Message::Changed);
+        })));
+
+        let debounce = self.debounce;
+        let thread_inner = inner.clone();
+        spawn(move||{
+            let mut stop = false;
+            while !stop {
+                match receiver.recv() {
+                    Ok(Message::Changed) => {}
+                    Ok(Message::Stop) | Err(_) => break,
+                }
+                //Wait until the commits calm down
+                loop {
+                    match receiver.recv_timeout(debounce) {
+                        Ok(Message::Changed) => {}
+                        Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                            stop = true;
+                            break;
+                        }
+                        Err(RecvTimeoutError::Timeout) => break,
+                    }
+                }
+                if listener.changes().iter().any(|state|thread_inner.tracks(*state)) {
+                    thread_inner.report(thread_inner.save());
+                }
+            }
+            unregister_listener(listener.id());
+        });
+
+        Ok(Store {
+            inner,
+            stop: sender,
+        })
+    }
+}
+
+/// Saves chosen States to a file whenever they commit and restores them at startup
+///
+/// The file contains the version of the format and the value of every tracked state under its
+/// key. The Store saves a last time when it is dropped.
+pub struct Store {
+    inner: Arc<StoreInner>,
+    stop: Sender<Message>,
+}
+
+impl Store {
+    /// Restores the State from the file, if it contains the key, and saves it on every commit
+    /// The State is tracked even if its stored value couldn't be restored.
+    pub fn track<T>(&self, key: impl Into<String>, state: &CloneState<T>) -> io::Result<()>
+        where T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+
+        let key = key.into();
+        let handle: Handle<T> = state.handle();
+
+        let stored = self.inner.values.lock().unwrap().get(&key).cloned();
+        let restored = match stored {
+            Some(stored) => serde_json::from_value::<T>(stored)
+                .map(|value|{
+                    handle.set(value);
+                })
+                .map_err(|error|io::Error::new(io::ErrorKind::InvalidData, error)),
+            None => Ok(()),
+        };
+
+        self.inner.tracked.lock().unwrap().push(Tracked {
+            key,
+            id: handle.id(),
+            snapshot: Box::new(move||{
+                handle.get().and_then(|value|serde_json::to_value(value).ok())
+            }),
+        });
+        restored
+    }
+
+    /// Saves all tracked States immediately
+    pub fn save(&self) -> io::Result<()> {
+        self.inner.save()
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        self.inner.report(self.inner.save());
+        let _ = self.stop.send(Message::Stop);
+    }
+}
@@ -1,8 +1,7 @@
-use crate::state::{StateID, update};
+use crate::state::{StateID, update, record};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{RwLock, Arc};
+use std::sync::{RwLock, Arc, Weak};
 use std::ops::{Deref, DerefMut};
-use std::any::Any;
 
 pub struct StateInner<T> {
     id: StateID,
@@ -28,6 +27,7 @@ impl<T: Clone> StateInner<T> {
     }
 
     pub fn use_value<R>(&self, operation: impl FnOnce(&T) -> R) -> R {
+        record(self.id);
         operation(self.value.read().unwrap().deref())
     }
 
@@ -52,21 +52,80 @@ impl<T: Clone> StateInner<T> {
 
 }
 
-impl<T: 'static + Clone> HandleInner for StateInner<T> {
-    fn update(&self, updater: Box<dyn FnOnce(&mut dyn Any)>) {
-        self.update_value(|value|{
-            updater(value as &mut dyn Any)
-        });
-    }
+/// A typed Handle to the value of a State, which can be send to other threads
+/// Handles are weak, they dont keep the State alive
+pub struct Handle<T> {
+    id: StateID,
+    inner: Weak<StateInner<T>>,
+}
 
-    fn id(&self) -> StateID {
+impl<T: Clone> Handle<T> {
+    pub(crate) fn new(inner: &Arc<StateInner<T>>) -> Self {
+        Handle {
+            id: inner.id(),
+            inner: Arc::downgrade(inner),
+        }
+    }
+    pub fn id(&self) -> StateID {
         self.id
     }
+    /// Returns false if the State was dropped
+    pub fn is_alive(&self) -> bool {
+        self.inner.strong_count() > 0
+    }
+    /// Changes the value of the State and notifies the listeners
+    /// Returns None if the State was dropped
+    pub fn update<R>(&self, operation: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.inner.upgrade().map(|inner|inner.update_value(operation).0)
+    }
+    /// Replaces the value of the State, returns false if the State was dropped
+    pub fn set(&self, value: T) -> bool {
+        self.update(move|old|*old = value).is_some()
+    }
+    /// Returns a copy of the current value or None if the State was dropped
+    pub fn get(&self) -> Option<T> {
+        self.inner.upgrade().map(|inner|inner.use_value(T::clone))
+    }
+    /// Starts a Batch of updates, which get committed with a single notification
+    pub fn batch(&self) -> Batch<T> {
+        Batch {
+            handle: self.clone(),
+            updates: Vec::new(),
+        }
+    }
 }
 
-pub trait HandleInner {
-    fn update(&self, updater: Box<dyn FnOnce(&mut dyn Any)>);
-    fn id(&self) -> StateID;
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle {
+            id: self.id,
+            inner: self.inner.clone(),
+        }
+    }
 }
 
-pub struct Handle(pub(crate) Arc<dyn HandleInner + Send + Sync>);
\ No newline at end of file
+/// Collects updates of a State, see Handle::batch
+pub struct Batch<T> {
+    handle: Handle<T>,
+    updates: Vec<Box<dyn FnOnce(&mut T) + Send>>,
+}
+
+impl<T: Clone> Batch<T> {
+    pub fn update(mut self, operation: impl FnOnce(&mut T) + Send + 'static) -> Self {
+        self.updates.push(Box::new(operation));
+        self
+    }
+    pub fn set(self, value: T) -> Self where T: Send + 'static {
+        self.update(move|old|*old = value)
+    }
+    /// Applies all updates in order as a single commit
+    /// Returns false if the State was dropped
+    pub fn commit(self) -> bool {
+        let Batch{handle, updates} = self;
+        handle.update(move|value|{
+            for update in updates {
+                update(value);
+            }
+        }).is_some()
+    }
+}
\ No newline at end of file
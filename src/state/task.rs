@@ -0,0 +1,105 @@
+use crate::state::CloneState;
+use once_cell::sync::Lazy;
+use futures::executor::ThreadPool;
+use futures::future::{abortable, AbortHandle};
+use std::future::Future;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static EXECUTOR: Lazy<ThreadPool> = Lazy::new(||{
+    ThreadPool::new().expect("couldn't start the task executor")
+});
+
+/// A running Future, whose result will be committed to a State
+pub struct Task {
+    abort: AbortHandle,
+    finished: Arc<AtomicBool>,
+}
+
+impl Task {
+    /// Cancels the Task, its result wont be committed
+    pub fn cancel(&self) {
+        self.abort.abort();
+    }
+    /// Returns true if the Task committed its result or was cancelled
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+}
+
+/// Runs the future on the executor and commits its output to the state
+pub fn spawn<T, F>(state: &CloneState<T>, future: F) -> Task
+    where T: Clone + Send + Sync + 'static, F: Future<Output=T> + Send + 'static {
+
+    let handle = state.handle();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let (future, abort) = abortable(async move {
+        let value = future.await;
+        handle.set(value);
+    });
+
+    {
+        let finished = finished.clone();
+        EXECUTOR.spawn_ok(async move {
+            let _ = future.await;
+            finished.store(true, Ordering::SeqCst);
+        });
+    }
+
+    Task {
+        abort,
+        finished,
+    }
+}
+
+/// The state of a value, which is loaded by a Task
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Loadable<T> {
+    Loading,
+    Error(String),
+    Ready(T),
+}
+
+impl<T> Loadable<T> {
+    pub fn is_loading(&self) -> bool {
+        match self {
+            Loadable::Loading => true,
+            _ => false,
+        }
+    }
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Loadable::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Loadable::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for Loadable<T> {
+    fn default() -> Self {
+        Loadable::Loading
+    }
+}
+
+/// Sets the state to Loading, runs the future on the executor and commits Ready or Error
+pub fn spawn_loadable<T, E, F>(state: &CloneState<Loadable<T>>, future: F) -> Task
+    where T: Clone + Send + Sync + 'static, E: Display, F: Future<Output=Result<T, E>> + Send + 'static {
+
+    state.handle().set(Loadable::Loading);
+
+    spawn(state, async move {
+        match future.await {
+            Ok(value) => Loadable::Ready(value),
+            Err(error) => Loadable::Error(error.to_string()),
+        }
+    })
+}
@@ -3,7 +3,8 @@ use std::sync::Arc;
 use druid_shell::MouseEvent;
 use crate::state::key::Key;
 use crate::widgets::{Widget, WidgetCompose};
-use crate::widgets::raw::click_listener;
+use crate::widgets::raw::{click_listener, click_command};
+use crate::app::command::Command;
 
 pub fn button<T: Clone>(widget: impl Widget<T>, style: impl Into<Option<Arc<StyleAtlas>>>, handler: impl FnMut(MouseEvent, Key<T>)) -> impl Widget<T> {
     click_listener(
@@ -11,3 +12,11 @@ pub fn button<T: Clone>(widget: impl Widget<T>, style: impl Into<Option<Arc<Styl
         handler
     )
 }
+
+/// A button which dispatches the command like a menu item or a key binding
+pub fn command_button<T: Clone>(widget: impl Widget<T>, style: impl Into<Option<Arc<StyleAtlas>>>, command: Command) -> impl Widget<T> {
+    click_command(
+        widget.wrap(DynamicStyle::new(style.into().unwrap_or(get_style("button")), true)),
+        command
+    )
+}
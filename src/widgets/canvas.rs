@@ -0,0 +1,93 @@
+use crate::widgets::Widget;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size};
+use druid_shell::piet::Piet;
+
+/// A Widget drawn by a closure, for charts, diagrams and other one-off drawings
+///
+/// Hit regions are computed from the size and the data after every layout and update. Mouse
+/// events are passed to the event closure together with the topmost region under the pointer,
+/// the last region of the list is the topmost one.
+pub struct Canvas<T, R = ()> {
+    draw: Box<dyn Fn(&mut Piet, Size, &T)>,
+    pref: PrefSize,
+    regions: Option<Box<dyn Fn(Size, &T) -> Vec<(R, Rect)>>>,
+    handler: Option<Box<dyn FnMut(Event, Option<&R>, Key<T>) -> EventResponse>>,
+    hit_regions: Vec<(R, Rect)>,
+    size: Size,
+}
+
+impl<T: Clone, R> Canvas<T, R> {
+    pub fn new(draw: impl Fn(&mut Piet, Size, &T) + 'static) -> Self {
+        Canvas {
+            draw: Box::new(draw),
+            pref: PrefSize::flexible(Size::new(100.0, 100.0)),
+            regions: None,
+            handler: None,
+            hit_regions: Vec::new(),
+            size: Size::ZERO,
+        }
+    }
+    pub fn pref_size(mut self, pref: PrefSize) -> Self {
+        self.pref = pref;
+        self
+    }
+    /// Sets the closure computing the hit regions
+    pub fn hit_regions(mut self, regions: impl Fn(Size, &T) -> Vec<(R, Rect)> + 'static) -> Self {
+        self.regions = Some(Box::new(regions));
+        self
+    }
+    /// Sets the closure handling the events, it gets the hit region under the pointer for
+    /// mouse events
+    pub fn on_event(mut self, handler: impl FnMut(Event, Option<&R>, Key<T>) -> EventResponse + 'static) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    fn compute_regions(&mut self, data: &T) {
+        if let Some(regions) = self.regions.as_ref() {
+            self.hit_regions = regions(self.size, data);
+        }
+    }
+}
+
+impl<T: Clone, R> Widget<T> for Canvas<T, R> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        (self.draw)(painter, size, data)
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse {
+        //The topmost region is the last one in the list
+        let Canvas {handler, hit_regions, ..} = self;
+        match handler.as_mut() {
+            Some(handler) => {
+                let pos = event.mouse_event().map(|mouse|mouse.pos);
+                let region = pos.and_then(|pos|hit_regions.iter()
+                    .rev()
+                    .find(|(_, rect)|rect.contains(pos))
+                    .map(|(region, _)|region));
+                handler(event, region, data)
+            }
+            None => EventResponse::NONE,
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.pref
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+        self.compute_regions(data);
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.compute_regions(new);
+        Change::Content(self.size.to_rect())
+    }
+}
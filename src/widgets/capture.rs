@@ -0,0 +1,22 @@
+use crate::widgets::Widget;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event};
+use druid_shell::kurbo::{Size, Vec2};
+
+/// Sends the mouse event to the child, which consumed the last MouseDown, at offset
+///
+/// Widgets with children send all mouse events to this child until the button is released, even
+/// outside of its bounds. If it is released outside, the child receives MouseExit before MouseUp.
+/// Returns the response and true if the child received MouseExit, the parent has to forget its
+/// hovered child then.
+pub(crate) fn captured_event<T: Clone, W: Widget<T> + ?Sized>(child: &mut W, offset: Vec2, size: Size, mut event: Event, mut context: WidgetContext, mut data: Key<T>) -> (EventResponse, bool) {
+    let released = if let Event::MouseUp(_) = event {true} else {false};
+    let inside = event.shift(offset, size);
+    let mut response = EventResponse::NONE;
+    let exited = released && !inside;
+    if exited {
+        response = child.handle_event(Event::MouseExit, context.id(), data.id()).shift(offset);
+    }
+    (response.merge(child.handle_event(event, context, data).shift(offset)), exited)
+}
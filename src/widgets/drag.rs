@@ -0,0 +1,230 @@
+use crate::widgets::Widget;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use crate::app::overlay::OverlayPainter;
+use druid_shell::kurbo::{Rect, Size, Point};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::MouseButton;
+use std::any::Any;
+use std::sync::Arc;
+
+/// The distance the mouse has to move with the pressed button before a drag starts
+pub(crate) const DRAG_THRESHOLD: f64 = 4.0;
+
+/// Paints the default preview of a drag, a translucent box
+pub(crate) fn default_preview() -> OverlayPainter {
+    Box::new(|painter: &mut Piet, size: Size|{
+        let background = painter.solid_brush(Color::rgba8(60, 110, 200, 120));
+        painter.fill(size.to_rect(), &background);
+        let border = painter.solid_brush(Color::rgb8(60, 110, 200));
+        painter.stroke(Rect::new(0.5, 0.5, size.width - 0.5, size.height - 0.5), &border, 1.0);
+    })
+}
+
+/// Starts a drag of the payload when the Widget is dragged with the left button
+pub struct DragSource<T, W, P> {
+    widget: W,
+    payload: Box<dyn FnMut(&T) -> Option<P>>,
+    preview: Option<(Size, Arc<dyn Fn(&mut Piet, Size)>)>,
+    on_end: Option<Box<dyn FnMut(bool, Key<T>)>>,
+    pressed: Option<Point>,
+    dragging: bool,
+    size: Size,
+}
+
+impl<T, W, P: Any> DragSource<T, W, P> {
+    /// The payload is created when the drag starts, None prevents the drag
+    pub fn new(widget: W, payload: impl FnMut(&T) -> Option<P> + 'static) -> Self {
+        DragSource {
+            widget,
+            payload: Box::new(payload),
+            preview: None,
+            on_end: None,
+            pressed: None,
+            dragging: false,
+            size: Size::ZERO,
+        }
+    }
+    /// Paints the preview following the mouse, by default a box of the size of the Widget
+    pub fn preview(mut self, size: Size, painter: impl Fn(&mut Piet, Size) + 'static) -> Self {
+        self.preview = Some((size, Arc::new(painter)));
+        self
+    }
+    /// Called after the drop, true if a drop target accepted the payload
+    /// Remove the item here to move it.
+    pub fn on_drag_end(mut self, handler: impl FnMut(bool, Key<T>) + 'static) -> Self {
+        self.on_end = Some(Box::new(handler));
+        self
+    }
+
+    fn start(&mut self, context: &mut WidgetContext, data: &T) {
+        self.pressed = None;
+        if let Some(payload) = (self.payload)(data) {
+            let (size, painter) = match self.preview.as_ref() {
+                Some((size, painter)) => {
+                    let painter = painter.clone();
+                    (*size, Box::new(move|piet: &mut Piet, size: Size|painter(piet, size)) as OverlayPainter)
+                }
+                None => (self.size, default_preview()),
+            };
+            context.start_drag(payload, size, painter);
+            self.dragging = true;
+        }
+    }
+}
+
+impl<T: Clone, W: Widget<T>, P: Any> Widget<T> for DragSource<T, W, P> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.widget.draw(painter, size, dirty_rect, context, data)
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<T>) -> EventResponse {
+        let response = self.widget.handle_event(event.clone(), context.id(), data.id());
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && response.is_present() => {
+                self.pressed = Some(mouse.pos);
+            }
+            Event::MouseMove(mouse) => {
+                if self.pressed.map_or(false, |pressed|(mouse.pos - pressed).hypot() > DRAG_THRESHOLD) {
+                    self.start(&mut context, &*data);
+                }
+            }
+            //The mouse left the Widget faster than the threshold
+            Event::MouseExit if self.pressed.is_some() => self.start(&mut context, &*data),
+            Event::MouseUp(_) => self.pressed = None,
+            Event::DragEnded(accepted) if self.dragging => {
+                self.dragging = false;
+                if let Some(on_end) = self.on_end.as_mut() {
+                    on_end(accepted, data);
+                }
+            }
+            _ => {}
+        }
+        response
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.widget.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+        self.widget.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        self.widget.build(context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.widget.update(new, old)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.widget.traverse_focus(context)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
+}
+
+/// Accepts dropped payloads of the type P
+/// The Widget is highlighted while a matching payload is dragged over it.
+pub struct DropTarget<T, W, P> {
+    widget: W,
+    on_drop: Box<dyn FnMut(P, Point, Key<T>)>,
+    accept: Option<Box<dyn Fn(&P) -> bool>>,
+    hovered: bool,
+    size: Size,
+}
+
+impl<T, W, P: Any> DropTarget<T, W, P> {
+    /// on_drop gets the payload and the position of the drop
+    pub fn new(widget: W, on_drop: impl FnMut(P, Point, Key<T>) + 'static) -> Self {
+        DropTarget {
+            widget,
+            on_drop: Box::new(on_drop),
+            accept: None,
+            hovered: false,
+            size: Size::ZERO,
+        }
+    }
+    /// Rejects the payloads for which the predicate returns false
+    pub fn accept(mut self, predicate: impl Fn(&P) -> bool + 'static) -> Self {
+        self.accept = Some(Box::new(predicate));
+        self
+    }
+
+    fn accepts(&self, context: &WidgetContext) -> bool {
+        context.drag_payload::<P>()
+            .map_or(false, |payload|self.accept.as_ref().map_or(true, |accept|accept(payload)))
+    }
+
+    fn set_hovered(&mut self, hovered: bool) -> EventResponse {
+        if self.hovered == hovered {
+            return EventResponse::NONE;
+        }
+        self.hovered = hovered;
+        EventResponse::changed(self.size, false)
+    }
+}
+
+impl<T: Clone, W: Widget<T>, P: Any> Widget<T> for DropTarget<T, W, P> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.widget.draw(painter, size, dirty_rect, context, data);
+        if self.hovered {
+            let brush = painter.solid_brush(Color::rgb8(60, 110, 200));
+            painter.stroke(Rect::new(1.0, 1.0, size.width - 1.0, size.height - 1.0), &brush, 2.0);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<T>) -> EventResponse {
+        match event {
+            Event::MouseEnter(_) | Event::MouseMove(_) => {
+                let hovered = self.accepts(&context);
+                let response = self.widget.handle_event(event, context, data);
+                response.merge(self.set_hovered(hovered))
+            }
+            Event::MouseExit => {
+                let response = self.widget.handle_event(event, context, data);
+                response.merge(self.set_hovered(false))
+            }
+            Event::MouseUp(mouse) if self.accepts(&context) => {
+                let response = self.widget.handle_event(Event::MouseUp(mouse.clone()), context.id(), data.id());
+                if let Some(payload) = context.accept_drop::<P>() {
+                    (self.on_drop)(payload, mouse.pos, data);
+                }
+                response.merge(self.set_hovered(false)).merge(EventResponse::DELETED)
+            }
+            event => self.widget.handle_event(event, context, data),
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.widget.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+        self.widget.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        self.widget.build(context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.widget.update(new, old)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.widget.traverse_focus(context)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
+}
@@ -0,0 +1,338 @@
+use crate::widgets::Widget;
+use crate::widgets::text::Label;
+use crate::widgets::style::{StyleAtlas, DynamicStyle, get_style};
+use crate::widgets::raw::Wrapper;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use crate::app::overlay::{OverlayToken, OverlayPainter};
+use druid_shell::kurbo::{Rect, Size, Point, Affine, BezPath, Vec2};
+use druid_shell::piet::{Piet, RenderContext, Color, Text, FontBuilder, TextLayoutBuilder, PietText};
+use druid_shell::KeyCode;
+use std::sync::Arc;
+use std::time::{Instant, Duration};
+
+const ROW_HEIGHT: f64 = 20.0;
+const ARROW_SPACE: f64 = 20.0;
+/// Typed characters within this time extend the search
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Popup {
+    token: OverlayToken,
+    highlighted: usize,
+    /// the first visible row
+    scroll: usize,
+    /// the number of rows fitting into the popup
+    rows: usize,
+}
+
+/// Shows the selected option and opens a list of all options in a popup on click or Space
+///
+/// While the popup is open, the arrow keys move the highlight, Enter or Space select the
+/// highlighted option and typing jumps to the first option starting with the typed text.
+/// Escape or a click outside close the popup without changing the selection.
+/// The popup opens upwards if there is more space above, and scrolls if it doesn't fit into the window.
+pub struct Dropdown<T> {
+    options: Vec<(T, String)>,
+    label: Label,
+    style: DynamicStyle,
+    popup: Option<Popup>,
+    /// the position in window coordinates at the last mouse event, None before the mouse
+    /// reached the Dropdown
+    origin: Option<Point>,
+    search: String,
+    last_search: Option<Instant>,
+    focused: bool,
+    size: Size,
+}
+
+impl<T: Clone + PartialEq> Dropdown<T> {
+    pub fn new() -> Self {
+        Dropdown::with_style(get_style("button"))
+    }
+    pub fn with_style(style: Arc<StyleAtlas>) -> Self {
+        Dropdown {
+            options: Vec::new(),
+            label: Label::new("", None),
+            style: DynamicStyle::new(style, true),
+            popup: None,
+            origin: None,
+            search: String::new(),
+            last_search: None,
+            focused: false,
+            size: Size::ZERO,
+        }
+    }
+    pub fn option(mut self, value: T, label: impl Into<String>) -> Self {
+        self.options.push((value, label.into()));
+        self
+    }
+
+    fn painter(&self, highlighted: usize, scroll: usize) -> OverlayPainter {
+        let labels = self.options.iter().map(|(_, label)|label.clone()).collect::<Vec<_>>();
+        Box::new(move|painter: &mut Piet, size: Size|{
+            let background = painter.solid_brush(Color::grey8(50));
+            painter.fill(size.to_rect(), &background);
+
+            let _ = painter.with_save(|painter|{
+                painter.clip(size.to_rect());
+                painter.transform(Affine::translate(Vec2::new(0.0, -(scroll as f64) * ROW_HEIGHT)));
+
+                let highlight = painter.solid_brush(Color::rgb8(60, 110, 200));
+                let y = highlighted as f64 * ROW_HEIGHT;
+                painter.fill(Rect::new(0.0, y, size.width, y + ROW_HEIGHT), &highlight);
+
+                let mut text = PietText::new();
+                let font = text.new_font_by_name("System", 13.0).build().unwrap();
+                let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+                for (row, label) in labels.iter().enumerate() {
+                    let layout = text.new_text_layout(&font, label, None).build().unwrap();
+                    painter.draw_text(&layout, (6.0, row as f64 * ROW_HEIGHT + 14.0), &brush);
+                }
+                Ok(())
+            });
+
+            let border = painter.solid_brush(Color::grey8(120));
+            painter.stroke(Rect::new(0.5, 0.5, size.width - 0.5, size.height - 0.5), &border, 1.0);
+        })
+    }
+
+    fn open(&mut self, context: &mut WidgetContext, data: &T) {
+        if self.options.is_empty() {
+            return;
+        }
+        let highlighted = self.options.iter()
+            .position(|(value, _)|value == data)
+            .unwrap_or(0);
+        //Opened by the keyboard before the mouse reached the Dropdown, the popup opens at the mouse
+        let anchor = self.origin.unwrap_or_else(||context.mouse_pos());
+        let bounds = context.window_size();
+        let height = self.options.len() as f64 * ROW_HEIGHT;
+        let below = (bounds.height - anchor.y - self.size.height).max(0.0);
+        let above = anchor.y.max(0.0);
+        let upwards = height > below && above > below;
+        let space = if upwards {above} else {below};
+        let rows = ((space.min(height) / ROW_HEIGHT) as usize).max(1).min(self.options.len());
+        let size = Size::new(self.size.width, rows as f64 * ROW_HEIGHT);
+        let origin = Point::new(
+            anchor.x.min(bounds.width - size.width).max(0.0),
+            if upwards {anchor.y - size.height} else {anchor.y + self.size.height},
+        );
+        //The selected option is the last visible row if the list is scrolled
+        let scroll = (highlighted + 1).saturating_sub(rows);
+        let token = context.open_overlay(Rect::from_origin_size(origin, size), self.painter(highlighted, scroll));
+        self.search.clear();
+        self.popup = Some(Popup {
+            token,
+            highlighted,
+            scroll,
+            rows,
+        });
+    }
+
+    fn close(&mut self, context: &mut WidgetContext) {
+        if let Some(popup) = self.popup.take() {
+            context.close_overlay(popup.token);
+        }
+    }
+
+    fn highlight(&mut self, context: &mut WidgetContext, row: usize) {
+        let (token, scroll) = match self.popup.as_mut() {
+            Some(popup) if popup.highlighted != row && row < self.options.len() => {
+                popup.highlighted = row;
+                //Scrolls the highlighted row into view
+                if row < popup.scroll {
+                    popup.scroll = row;
+                } else if row >= popup.scroll + popup.rows {
+                    popup.scroll = row + 1 - popup.rows;
+                }
+                (popup.token, popup.scroll)
+            }
+            _ => return,
+        };
+        let painter = self.painter(row, scroll);
+        context.update_overlay(token, painter);
+    }
+
+    /// Scrolls the popup by one row in the direction of the wheel
+    fn scroll(&mut self, context: &mut WidgetContext, delta: f64) {
+        let max = self.options.len();
+        let (token, highlighted, scroll) = match self.popup.as_mut() {
+            Some(popup) => {
+                let scroll = if delta > 0.0 {
+                    (popup.scroll + 1).min(max - popup.rows)
+                } else if delta < 0.0 {
+                    popup.scroll.saturating_sub(1)
+                } else {
+                    popup.scroll
+                };
+                if scroll == popup.scroll {
+                    return;
+                }
+                popup.scroll = scroll;
+                (popup.token, popup.highlighted, scroll)
+            }
+            None => return,
+        };
+        let painter = self.painter(highlighted, scroll);
+        context.update_overlay(token, painter);
+    }
+
+    fn select(&mut self, context: &mut WidgetContext, data: &mut Key<T>, row: usize) {
+        self.close(context);
+        if let Some((value, _)) = self.options.get(row) {
+            if **data != *value {
+                let value = value.clone();
+                data.change(|selected|*selected = value);
+            }
+        }
+    }
+
+    /// Jumps to the first option starting with the typed text
+    fn search_for(&mut self, context: &mut WidgetContext, text: &str) {
+        let now = Instant::now();
+        if self.last_search.map_or(true, |last|now - last > SEARCH_TIMEOUT) {
+            self.search.clear();
+        }
+        self.last_search = Some(now);
+        self.search.push_str(&text.to_lowercase());
+
+        let search = &self.search;
+        let found = self.options.iter()
+            .position(|(_, label)|label.to_lowercase().starts_with(search.as_str()));
+        if let Some(row) = found {
+            self.highlight(context, row);
+        }
+    }
+
+    fn handle_popup_event(&mut self, event: Event, context: &mut WidgetContext, data: &mut Key<T>) {
+        let (highlighted, scroll) = match self.popup.as_ref() {
+            Some(popup) => (popup.highlighted, popup.scroll),
+            None => return,
+        };
+        match event {
+            Event::MouseMove(mouse) => {
+                self.highlight(context, scroll + (mouse.pos.y / ROW_HEIGHT) as usize);
+            }
+            Event::MouseDown(mouse) => {
+                self.select(context, data, scroll + (mouse.pos.y / ROW_HEIGHT) as usize);
+            }
+            Event::Wheel(mouse) => {
+                self.scroll(context, mouse.wheel_delta.y);
+            }
+            Event::KeyDown(key_event) => {
+                match key_event.key_code {
+                    KeyCode::Escape => self.close(context),
+                    KeyCode::Return | KeyCode::Space => self.select(context, data, highlighted),
+                    KeyCode::ArrowUp if highlighted > 0 => self.highlight(context, highlighted - 1),
+                    KeyCode::ArrowDown => self.highlight(context, highlighted + 1),
+                    _ => {
+                        if let Some(text) = key_event.text() {
+                            if !text.chars().any(char::is_control) {
+                                self.search_for(context, text);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Widget<T> for Dropdown<T> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        let style = &self.style;
+        let _ = painter.with_save(|painter|{
+            style.draw(painter, size, dirty_rect);
+            Ok(())
+        });
+
+        let mut arrow = BezPath::new();
+        let center = Point::new(size.width - ARROW_SPACE / 2.0, size.height / 2.0);
+        arrow.move_to(center + Vec2::new(-4.0, -2.0));
+        arrow.line_to(center + Vec2::new(4.0, -2.0));
+        arrow.line_to(center + Vec2::new(0.0, 3.0));
+        arrow.close_path();
+        let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+        painter.fill(arrow, &brush);
+
+        let padding = self.style.get_left_top_padding();
+        painter.transform(Affine::translate(padding.to_vec2()));
+        self.label.draw(painter, size, dirty_rect, context, &());
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<T>) -> EventResponse {
+        let styled = self.style.handle_event(event.clone()).content_change();
+        if let Some(mouse) = event.mouse_event() {
+            self.origin = Some(context.window_origin(mouse.pos));
+        }
+        let mut consumed = false;
+        match event {
+            Event::Overlay(token, popup_event) => {
+                if self.popup.as_ref().map_or(false, |popup|popup.token == token) {
+                    self.handle_popup_event(*popup_event, &mut context, &mut data);
+                    consumed = true;
+                }
+            }
+            Event::OverlayClosed(token) => {
+                if self.popup.as_ref().map_or(false, |popup|popup.token == token) {
+                    self.popup = None;
+                }
+            }
+            Event::MouseDown(_) => {
+                if self.popup.is_some() {
+                    self.close(&mut context);
+                } else {
+                    self.open(&mut context, &*data);
+                }
+                consumed = true;
+            }
+            Event::KeyDown(key_event) if self.focused && self.popup.is_none() => {
+                match key_event.key_code {
+                    KeyCode::Space | KeyCode::Return | KeyCode::ArrowDown => {
+                        self.open(&mut context, &*data);
+                        consumed = true;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        if styled {
+            EventResponse::changed(self.size, consumed)
+        } else {
+            EventResponse::unchanged(consumed)
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        let width = self.options.iter()
+            .map(|(_, label)|Label::new(label.as_str(), None).width())
+            .fold(self.label.width(), f64::max);
+        let padding = self.style.get_full_padding();
+        PrefSize::fixed(Size::new(width + 8.0 + ARROW_SPACE + padding.width, 17.0 + padding.height))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        let text = self.options.iter()
+            .find(|(value, _)|value == new)
+            .map_or("", |(_, label)|label.as_str());
+        *self.label.set_text() = text.to_string();
+        self.label.recalc_text();
+        Change::Content(self.size.to_rect())
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.focused = !self.focused;
+        self.style.set_focused(self.focused);
+        self.focused
+    }
+}
@@ -0,0 +1,432 @@
+use crate::widgets::{Widget, StateWrapper, state};
+use crate::state::key::Key;
+use crate::state::{CloneState, Loadable, Task, spawn_loadable};
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Point};
+use druid_shell::piet::{Piet, RenderContext, ImageFormat, InterpolationMode};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::marker::PhantomData;
+
+/// Where the encoded PNG or JPEG data of an Image comes from
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ImageSource {
+    Bytes(Arc<[u8]>),
+    Path(PathBuf),
+}
+
+impl From<&[u8]> for ImageSource {
+    fn from(bytes: &[u8]) -> Self {
+        ImageSource::Bytes(bytes.into())
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        ImageSource::Path(path.into())
+    }
+}
+
+/// Decoded pixels in RGBA order
+pub struct ImageData {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(bytes).map_err(|error|error.to_string())?;
+        let image = image.to_rgba();
+        Ok(ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            pixels: image.into_raw(),
+        })
+    }
+    pub fn size(&self) -> Size {
+        Size::new(self.width as f64, self.height as f64)
+    }
+}
+
+/// Bytes are compared by their content, equal images from different sources share an entry
+#[derive(Hash, PartialEq, Eq)]
+enum CacheKey {
+    Bytes(Arc<[u8]>),
+    Path(PathBuf),
+}
+
+/// Decoded images shared by all Image widgets
+/// Entries are dropped by clear_image_cache or when no Widget uses them anymore.
+static CACHE: Lazy<Mutex<HashMap<CacheKey, Arc<ImageData>>>> = Lazy::new(||Mutex::new(HashMap::new()));
+
+/// Decodes the image or returns the cached one
+pub fn load_image(source: &ImageSource) -> Result<Arc<ImageData>, String> {
+    let key = match source {
+        ImageSource::Bytes(bytes) => CacheKey::Bytes(bytes.clone()),
+        ImageSource::Path(path) => CacheKey::Path(path.clone()),
+    };
+
+    if let Some(image) = CACHE.lock().unwrap().get(&key) {
+        return Ok(image.clone());
+    }
+
+    let image = Arc::new(match source {
+        ImageSource::Bytes(bytes) => ImageData::decode(bytes)?,
+        ImageSource::Path(path) => {
+            let bytes = std::fs::read(path).map_err(|error|format!("{}: {}", path.display(), error))?;
+            ImageData::decode(&bytes)?
+        }
+    });
+
+    let mut cache = CACHE.lock().unwrap();
+    cache.retain(|_, image|Arc::strong_count(image) > 1);
+    cache.insert(key, image.clone());
+    Ok(image)
+}
+
+/// Drops all cached images, which aren't shown by a Widget
+pub fn clear_image_cache() {
+    CACHE.lock().unwrap().retain(|_, image|Arc::strong_count(image) > 1);
+}
+
+/// How an Image is scaled into the space of the Widget
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FitMode {
+    /// Scales the image to fit completely, keeping the aspect ratio
+    Contain,
+    /// Scales the image to fill the space, keeping the aspect ratio and cropping the rest
+    Cover,
+    /// Stretches the image to the space
+    Fill,
+    /// Shows the image in its intrinsic size, centered and cropped
+    None,
+    /// Like Contain, but never scales the image up
+    ScaleDown,
+}
+
+impl FitMode {
+    /// Returns the rect of the image inside of the space
+    pub fn fit(&self, image: Size, space: Size) -> Rect {
+        if image.width <= 0.0 || image.height <= 0.0 {
+            return Rect::ZERO;
+        }
+        let contain = (space.width / image.width).min(space.height / image.height);
+        let scale = match self {
+            FitMode::Fill => return space.to_rect(),
+            FitMode::Contain => contain,
+            FitMode::Cover => (space.width / image.width).max(space.height / image.height),
+            FitMode::None => 1.0,
+            FitMode::ScaleDown => contain.min(1.0),
+        };
+        let size = Size::new(image.width * scale, image.height * scale);
+        Rect::from_origin_size(Point::new((space.width - size.width) / 2.0,
+                                          (space.height - size.height) / 2.0), size)
+    }
+}
+
+type PietImage = <Piet<'static> as RenderContext>::Image;
+
+/// The decoded image and its device image, shared by Image and DynImage
+struct ImageView {
+    data: Option<Arc<ImageData>>,
+    image: Option<PietImage>,
+    /// the error of the last load or of the creation of the device image
+    error: Option<String>,
+    on_error: Option<Box<dyn Fn(&str)>>,
+    fit: FitMode,
+    interpolation: InterpolationMode,
+    size: Size,
+}
+
+impl ImageView {
+    fn new(source: Option<&ImageSource>) -> Self {
+        let mut view = ImageView {
+            data: None,
+            image: None,
+            error: None,
+            on_error: None,
+            fit: FitMode::Contain,
+            interpolation: InterpolationMode::Bilinear,
+            size: Size::ZERO,
+        };
+        if let Some(source) = source {
+            view.load(source);
+        }
+        view
+    }
+    /// Loads the image, returns true if the intrinsic size changed
+    fn load(&mut self, source: &ImageSource) -> bool {
+        let old = self.intrinsic_size();
+        self.error = None;
+        self.data = match load_image(source) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                self.fail(error);
+                None
+            }
+        };
+        self.image = None;
+        old != self.intrinsic_size()
+    }
+    /// Shows the loaded image, the previous image stays visible while loading
+    /// Returns true if the intrinsic size changed.
+    fn set_loaded(&mut self, loaded: &Loadable<Arc<ImageData>>) -> bool {
+        let old = self.intrinsic_size();
+        match loaded {
+            Loadable::Loading => return false,
+            Loadable::Ready(data) => {
+                self.error = None;
+                self.data = Some(data.clone());
+            }
+            Loadable::Error(error) => {
+                self.data = None;
+                self.fail(error.clone());
+            }
+        }
+        self.image = None;
+        old != self.intrinsic_size()
+    }
+    fn fail(&mut self, error: String) {
+        if let Some(on_error) = self.on_error.as_ref() {
+            on_error(&error);
+        }
+        self.error = Some(error);
+    }
+    /// The handler receives the error of the last load immediately
+    fn set_on_error(&mut self, handler: Box<dyn Fn(&str)>) {
+        if let Some(error) = self.error.as_ref() {
+            handler(error);
+        }
+        self.on_error = Some(handler);
+    }
+    fn intrinsic_size(&self) -> Size {
+        self.data.as_ref().map_or(Size::ZERO, |data|data.size())
+    }
+    fn draw(&mut self, painter: &mut Piet, size: Size) {
+        let data = match self.data.as_ref() {
+            Some(data) => data,
+            None => return,
+        };
+        if self.image.is_none() {
+            match painter.make_image(data.width, data.height, &data.pixels, ImageFormat::RgbaSeparate) {
+                Ok(image) => self.image = Some(image),
+                Err(error) => {
+                    self.data = None;
+                    self.fail(error.to_string());
+                    return;
+                }
+            }
+        }
+        if let Some(image) = self.image.as_ref() {
+            let rect = self.fit.fit(data.size(), size);
+            let interpolation = self.interpolation;
+            let _ = painter.with_save(|painter|{
+                painter.clip(size.to_rect());
+                painter.draw_image(image, rect, interpolation);
+                Ok(())
+            });
+        }
+    }
+    fn pref_size(&self) -> PrefSize {
+        match self.fit {
+            FitMode::None => PrefSize::fixed(self.intrinsic_size()),
+            _ => PrefSize::flexible(self.intrinsic_size()),
+        }
+    }
+}
+
+/// Shows a PNG or JPEG image
+/// Nothing is shown if the image couldn't be loaded, see try_new and on_error.
+pub struct Image {
+    view: ImageView,
+}
+
+impl Image {
+    pub fn new(source: impl Into<ImageSource>) -> Self {
+        Image {
+            view: ImageView::new(Some(&source.into())),
+        }
+    }
+    /// Returns the error if the image couldn't be loaded
+    pub fn try_new(source: impl Into<ImageSource>) -> Result<Self, String> {
+        let image = Image::new(source);
+        match image.view.error.clone() {
+            Some(error) => Err(error),
+            None => Ok(image),
+        }
+    }
+    /// Is called if the image couldn't be loaded or drawn
+    pub fn on_error(mut self, handler: impl Fn(&str) + 'static) -> Self {
+        self.view.set_on_error(Box::new(handler));
+        self
+    }
+    pub fn fit(mut self, fit: FitMode) -> Self {
+        self.view.fit = fit;
+        self
+    }
+    pub fn interpolation(mut self, interpolation: InterpolationMode) -> Self {
+        self.view.interpolation = interpolation;
+        self
+    }
+}
+
+impl<T: Clone> Widget<T> for Image {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.view.draw(painter, size)
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse {
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.view.pref_size()
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.view.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        Change::None
+    }
+}
+
+/// Shows the decoded image of a DynImage, bound to the State the Task commits to
+struct LoadedImage {
+    view: ImageView,
+}
+
+impl Widget<Loadable<Arc<ImageData>>> for LoadedImage {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &Loadable<Arc<ImageData>>) {
+        self.view.draw(painter, size)
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<Loadable<Arc<ImageData>>>) -> EventResponse {
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &Loadable<Arc<ImageData>>) -> PrefSize {
+        self.view.pref_size()
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &Loadable<Arc<ImageData>>) {
+        self.view.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &Loadable<Arc<ImageData>>, old: Option<&Loadable<Arc<ImageData>>>) -> Change {
+        if self.view.set_loaded(new) {
+            Change::Bounds
+        } else {
+            Change::Content(self.view.size.to_rect())
+        }
+    }
+}
+
+/// Shows the image returned by update for the current value
+/// The image is loaded and decoded on the task executor, the previous image is shown meanwhile.
+pub struct DynImage<T, F> {
+    /// the view until it is moved into the StateWrapper by build
+    view: Option<ImageView>,
+    image: StateWrapper,
+    loaded: CloneState<Loadable<Arc<ImageData>>>,
+    task: Option<Task>,
+    source: Option<ImageSource>,
+    update: F,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Clone, F: Fn(&T) -> ImageSource> DynImage<T, F> {
+    pub fn new(update: F) -> Self {
+        DynImage {
+            view: Some(ImageView::new(None)),
+            image: StateWrapper::Removed,
+            loaded: CloneState::new(Loadable::Loading),
+            task: None,
+            source: None,
+            update,
+            phantom: PhantomData,
+        }
+    }
+    fn view(&mut self) -> &mut ImageView {
+        self.view.as_mut().expect("DynImage is configured after it was built")
+    }
+    /// Is called if an image couldn't be loaded or drawn
+    pub fn on_error(mut self, handler: impl Fn(&str) + 'static) -> Self {
+        self.view().set_on_error(Box::new(handler));
+        self
+    }
+    pub fn fit(mut self, fit: FitMode) -> Self {
+        self.view().fit = fit;
+        self
+    }
+    pub fn interpolation(mut self, interpolation: InterpolationMode) -> Self {
+        self.view().interpolation = interpolation;
+        self
+    }
+}
+
+impl<T: Clone, F: Fn(&T) -> ImageSource> Widget<T> for DynImage<T, F> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.image.draw(painter, size, dirty_rect, context, data)
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse {
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.image.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.image.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        if let Some(view) = self.view.take() {
+            self.image = state(&self.loaded, LoadedImage { view });
+        }
+        Widget::<T>::build(&mut self.image, context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        let source = (self.update)(new);
+        if self.source.as_ref() == Some(&source) {
+            return Change::None;
+        }
+        if let Some(task) = self.task.take() {
+            task.cancel();
+        }
+        self.source = Some(source.clone());
+        self.task = Some(spawn_loadable(&self.loaded, async move {
+            load_image(&source)
+        }));
+        //The LoadedImage is updated when the Task commits
+        Change::None
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        if let Lifecycle::WidgetRemoved = event {
+            if let Some(task) = self.task.take() {
+                task.cancel();
+            }
+        }
+        Widget::<T>::lifecycle(&mut self.image, event, context)
+    }
+}
@@ -1,10 +1,11 @@
 use crate::widgets::layout::{WidgetList, ChildMeta, Layout};
 use crate::widgets::Widget;
+use crate::widgets::capture::captured_event;
 use crate::state::key::Key;
 use druid_shell::kurbo::{Size, Affine, Rect};
 use druid_shell::piet::{Piet, RenderContext};
 use crate::app::widget_graph::WidgetContext;
-use crate::app::event::{Event, EventResponse, Change};
+use crate::app::event::{Event, EventResponse, Change, Lifecycle};
 use crate::app::size::PrefSize;
 
 type List<T, Meta> = Vec<(Box<dyn Widget<T>>, ChildMeta<Meta>)>;
@@ -35,6 +36,8 @@ pub struct Container<T: Clone, L: Layout> {
     widgets: List<T, L::Meta>,
     layout: L,
     mouse_focus: Option<u32>,
+    /// the child which consumed the last MouseDown
+    captured: Option<u32>,
     focus: Option<u32>,
 }
 
@@ -44,6 +47,7 @@ impl<T: Clone, L: Layout> Container<T, L> {
             widgets: Vec::new(),
             layout,
             mouse_focus: None,
+            captured: None,
             focus: None,
         }
     }
@@ -83,6 +87,23 @@ impl<T: Clone + 'static, L: Layout> Widget<T> for Container<T, L> {
     fn handle_event(&mut self, mut event: Event, mut context: WidgetContext, mut data: Key<T>) -> EventResponse {
         let mut response = EventResponse::NONE;
 
+        if event.mouse_event().is_some() && context.is_pointer_captured() {
+            if let Some(index) = self.captured {
+                if let Event::MouseUp(_) = event {
+                    self.captured = None;
+                }
+                let (child, meta) = &mut self.widgets[index as usize];
+                let (response, exited) = captured_event(&mut **child, meta.offset, meta.size, event, context, data);
+                if exited {
+                    self.mouse_focus = None;
+                }
+                return response;
+            }
+        }
+
+        if let Event::MouseDown(_) = event {
+            self.captured = None;
+        }
         if let Some(me) = event.mouse_event() {
             let mut consumer = None;
             for (index, (child, meta)) in self.widgets.iter().enumerate() {
@@ -110,12 +131,21 @@ impl<T: Clone + 'static, L: Layout> Widget<T> for Container<T, L> {
                     //Do nothing (already done)!
                 } else {
                     //Process Event
+                    let pressed = if let Event::MouseDown(_) = event {true} else {false};
                     if event.shift(meta.offset, meta.size) {
-                        response = response.merge(child.handle_event(event, context.id(), data.id()).shift(meta.offset));
+                        let child_response = child.handle_event(event, context.id(), data.id());
+                        if pressed {
+                            self.captured = if child_response.is_present() {None} else {Some(new)};
+                        }
+                        response = response.merge(child_response.shift(meta.offset));
                     }
                 }
             }
             self.mouse_focus = consumer;
+        } else if event.is_broadcast() {
+            for (child, meta) in self.widgets.iter_mut() {
+                response = response.merge(child.handle_event(event.clone(), context.id(), data.id()).shift(meta.offset));
+            }
         } else if let Some(id) = self.focus {
             response = response.merge(self.widgets[id as usize].0.handle_event(event, context, data));
         }
@@ -131,10 +161,14 @@ impl<T: Clone + 'static, L: Layout> Widget<T> for Container<T, L> {
     }
 
     fn layout(&mut self, size: Size, mut context: WidgetContext, data: &T) {
+        let old_sizes = self.widgets.iter().map(|(_, meta)|meta.size).collect::<Vec<_>>();
         self.layout.layout(size, &mut self.widgets);
-        for (child, meta) in self.widgets.iter_mut() {
+        for ((child, meta), old_size) in self.widgets.iter_mut().zip(old_sizes) {
             meta.size = meta.size.expand();
             meta.offset = meta.offset.expand();
+            if meta.size != old_size {
+                child.lifecycle(&Lifecycle::SizeChanged(meta.size), context.id());
+            }
             child.layout(meta.size, context.id(), data);
         }
     }
@@ -165,4 +199,13 @@ impl<T: Clone + 'static, L: Layout> Widget<T> for Container<T, L> {
         self.focus = None;
         false
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        //The children get their SizeChanged from layout
+        if event.is_broadcast() {
+            for (child, _) in self.widgets.iter_mut() {
+                child.lifecycle(event, context.id());
+            }
+        }
+    }
 }
\ No newline at end of file
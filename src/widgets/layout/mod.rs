@@ -2,10 +2,12 @@ mod hbox;
 mod container;
 mod list_container;
 mod vbox;
+mod split;
 
 pub use hbox::HBox;
 pub use vbox::VBox;
 pub use container::Container;
+pub use split::Split;
 
 use druid_shell::kurbo::{Vec2, Size};
 use crate::app::size::PrefSize;
@@ -0,0 +1,393 @@
+use crate::widgets::Widget;
+use crate::widgets::capture::captured_event;
+use crate::widgets::Orientation;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Point};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::Cursor;
+
+const DIVIDER: f64 = 6.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Part {
+    First,
+    Divider,
+    Second,
+}
+
+struct Pane {
+    widget: Box<dyn Widget<f64>>,
+    pref: PrefSize,
+    offset: Vec2,
+    size: Size,
+}
+
+impl Pane {
+    fn new(widget: Box<dyn Widget<f64>>) -> Self {
+        Pane {
+            widget,
+            pref: PrefSize::zero(),
+            offset: Vec2::ZERO,
+            size: Size::ZERO,
+        }
+    }
+}
+
+/// Two children next to each other with a draggable divider between them
+///
+/// The data is the share of the first child of the available space. The divider never shrinks a
+/// child below its min size, a double click resets it to the default ratio. The children usually
+/// are StateWrappers of their own state.
+pub struct Split {
+    first: Pane,
+    second: Pane,
+    orientation: Orientation,
+    default_ratio: f64,
+    dragging: bool,
+    mouse_focus: Option<Part>,
+    /// the pane which consumed the last MouseDown
+    captured: Option<Part>,
+    focus: Option<Part>,
+    size: Size,
+}
+
+impl Split {
+    /// Places first left of second
+    pub fn horizontal(first: impl Widget<f64> + 'static, second: impl Widget<f64> + 'static) -> Self {
+        Split::new(Orientation::Horizontal, Box::new(first), Box::new(second))
+    }
+    /// Places first above second
+    pub fn vertical(first: impl Widget<f64> + 'static, second: impl Widget<f64> + 'static) -> Self {
+        Split::new(Orientation::Vertical, Box::new(first), Box::new(second))
+    }
+    fn new(orientation: Orientation, first: Box<dyn Widget<f64>>, second: Box<dyn Widget<f64>>) -> Self {
+        Split {
+            first: Pane::new(first),
+            second: Pane::new(second),
+            orientation,
+            default_ratio: 0.5,
+            dragging: false,
+            mouse_focus: None,
+            captured: None,
+            focus: None,
+            size: Size::ZERO,
+        }
+    }
+    /// The ratio restored by a double click on the divider
+    pub fn default_ratio(mut self, ratio: f64) -> Self {
+        self.default_ratio = ratio.max(0.0).min(1.0);
+        self
+    }
+
+    fn along(&self, size: Size) -> f64 {
+        match self.orientation {
+            Orientation::Horizontal => size.width,
+            Orientation::Vertical => size.height,
+        }
+    }
+
+    fn across(&self, size: Size) -> f64 {
+        match self.orientation {
+            Orientation::Horizontal => size.height,
+            Orientation::Vertical => size.width,
+        }
+    }
+
+    fn size_of(&self, along: f64, across: f64) -> Size {
+        match self.orientation {
+            Orientation::Horizontal => Size::new(along, across),
+            Orientation::Vertical => Size::new(across, along),
+        }
+    }
+
+    fn vec_of(&self, along: f64) -> Vec2 {
+        match self.orientation {
+            Orientation::Horizontal => Vec2::new(along, 0.0),
+            Orientation::Vertical => Vec2::new(0.0, along),
+        }
+    }
+
+    /// Returns the length of the first child for the ratio, respecting the min sizes
+    fn first_length(&self, ratio: f64) -> f64 {
+        let available = (self.along(self.size) - DIVIDER).max(0.0);
+        let min_first = self.along(self.first.pref.min);
+        let min_second = self.along(self.second.pref.min);
+        let length = available * ratio.max(0.0).min(1.0);
+        length.min(available - min_second).max(min_first).min(available).max(0.0)
+    }
+
+    fn ratio_at(&self, pos: Point) -> f64 {
+        let available = (self.along(self.size) - DIVIDER).max(0.0);
+        if available <= 0.0 {
+            return self.default_ratio;
+        }
+        let along = self.along(Size::new(pos.x, pos.y)) - DIVIDER / 2.0;
+        let length = self.first_length(along / available);
+        length / available
+    }
+
+    fn part_at(&self, pos: Point) -> Option<Part> {
+        if !self.size.to_rect().contains(pos) {
+            return None;
+        }
+        let along = self.along(Size::new(pos.x, pos.y));
+        let divider = self.along(self.first.size);
+        Some(if along < divider {
+            Part::First
+        } else if along < divider + DIVIDER {
+            Part::Divider
+        } else {
+            Part::Second
+        })
+    }
+
+    fn pane(&mut self, part: Part) -> Option<&mut Pane> {
+        match part {
+            Part::First => Some(&mut self.first),
+            Part::Second => Some(&mut self.second),
+            Part::Divider => None,
+        }
+    }
+
+    fn resize_cursor(&self) -> Cursor {
+        match self.orientation {
+            Orientation::Horizontal => Cursor::ResizeLeftRight,
+            Orientation::Vertical => Cursor::ResizeUpDown,
+        }
+    }
+
+    fn set(&self, data: &mut Key<f64>, ratio: f64) {
+        if (**data - ratio).abs() > std::f64::EPSILON {
+            data.change(|current|*current = ratio);
+        }
+    }
+
+    /// Sends the mouse event to the pane which captured the mouse
+    fn captured_event(&mut self, part: Part, event: Event, context: WidgetContext, data: Key<f64>) -> EventResponse {
+        if let Event::MouseUp(_) = event {
+            self.captured = None;
+        }
+        let (response, exited) = match self.pane(part) {
+            Some(pane) => captured_event(&mut *pane.widget, pane.offset, pane.size, event, context, data),
+            None => (EventResponse::NONE, false),
+        };
+        if exited {
+            //The next MouseMove enters the part below the pointer
+            self.mouse_focus = None;
+        }
+        response
+    }
+
+    /// Moves the mouse focus to the part, sending MouseExit and MouseEnter
+    fn enter(&mut self, part: Option<Part>, event: &Event, context: &mut WidgetContext, data: &mut Key<f64>) -> EventResponse {
+        let mut response = EventResponse::NONE;
+        if part == self.mouse_focus {
+            return response;
+        }
+        if let Some(old) = self.mouse_focus.take() {
+            if old == Part::Divider {
+                context.set_cursor(Cursor::Arrow);
+            }
+            if let Some(pane) = self.pane(old) {
+                response = response.merge(pane.widget.handle_event(Event::MouseExit, context.id(), data.id()).shift(pane.offset));
+            }
+        }
+        if let Some(new) = part {
+            if new == Part::Divider {
+                context.set_cursor(self.resize_cursor());
+            }
+            if let (Some(pane), Some(mouse)) = (self.pane(new), event.mouse_event()) {
+                let mut mouse = mouse.clone();
+                mouse.pos -= pane.offset;
+                response = response.merge(pane.widget.handle_event(Event::MouseEnter(mouse), context.id(), data.id()).shift(pane.offset));
+            }
+        }
+        self.mouse_focus = part;
+        response
+    }
+}
+
+impl Widget<f64> for Split {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &f64) {
+        for pane in [&mut self.first, &mut self.second].iter_mut() {
+            let child_dirty_rect = (dirty_rect - pane.offset).intersect(pane.size.to_rect());
+            if child_dirty_rect.area() > 0.0 {
+                let _ = painter.with_save(|painter|{
+                    painter.transform(Affine::translate(pane.offset));
+                    pane.widget.draw(painter, pane.size, child_dirty_rect, context.id(), data);
+                    Ok(())
+                });
+            }
+        }
+
+        let divider = Rect::from_origin_size(self.vec_of(self.along(self.first.size)).to_point(),
+                                             self.size_of(DIVIDER, self.across(size)));
+        let color = if self.dragging || self.mouse_focus == Some(Part::Divider) {
+            Color::grey8(120)
+        } else {
+            Color::grey8(70)
+        };
+        let brush = painter.solid_brush(color);
+        painter.fill(divider, &brush);
+    }
+
+    fn handle_event(&mut self, mut event: Event, mut context: WidgetContext, mut data: Key<f64>) -> EventResponse {
+        if let Some(mouse) = event.mouse_event().cloned() {
+            if self.dragging {
+                match event {
+                    Event::MouseMove(_) => {
+                        let ratio = self.ratio_at(mouse.pos);
+                        self.set(&mut data, ratio);
+                    }
+                    Event::MouseUp(_) => {
+                        self.dragging = false;
+                        if self.part_at(mouse.pos) != Some(Part::Divider) {
+                            context.set_cursor(Cursor::Arrow);
+                        }
+                    }
+                    _ => {}
+                }
+                return EventResponse::DELETED;
+            }
+
+            if let Event::MouseDown(_) = event {
+                self.captured = None;
+            }
+            if context.is_pointer_captured() {
+                if let Some(part) = self.captured {
+                    return self.captured_event(part, event, context, data);
+                }
+            }
+
+            let part = self.part_at(mouse.pos);
+            let mut response = self.enter(part, &event, &mut context, &mut data);
+            match part {
+                Some(Part::Divider) => {
+                    if let Event::MouseDown(_) = event {
+                        if mouse.count >= 2 {
+                            let ratio = self.default_ratio;
+                            self.set(&mut data, ratio);
+                        } else {
+                            self.dragging = true;
+                        }
+                        response = response.merge(EventResponse::changed(self.size, true));
+                    }
+                }
+                Some(part) => {
+                    if let Event::MouseEnter(_) = event {
+                        //Already entered
+                    } else if let Some(pane) = self.pane(part) {
+                        let pressed = if let Event::MouseDown(_) = event {true} else {false};
+                        if event.shift(pane.offset, pane.size) {
+                            let pane_response = pane.widget.handle_event(event, context, data);
+                            response = response.merge(pane_response.shift(pane.offset));
+                            if pressed && !pane_response.is_present() {
+                                self.captured = Some(part);
+                            }
+                        }
+                    }
+                }
+                None => {}
+            }
+            return response;
+        }
+
+        match event {
+            Event::MouseExit => {
+                self.dragging = false;
+                self.enter(None, &event, &mut context, &mut data)
+            }
+            event if event.is_broadcast() => {
+                let first = self.first.widget.handle_event(event.clone(), context.id(), data.id()).shift(self.first.offset);
+                let second = self.second.widget.handle_event(event, context, data).shift(self.second.offset);
+                first.merge(second)
+            }
+            event => {
+                let focus = self.focus;
+                match focus.and_then(move |part|self.pane(part)) {
+                    Some(pane) => pane.widget.handle_event(event, context, data),
+                    None => EventResponse::NONE,
+                }
+            }
+        }
+    }
+
+    fn get_pref_size(&mut self, mut context: WidgetContext, data: &f64) -> PrefSize {
+        self.first.pref = self.first.widget.get_pref_size(context.id(), data);
+        self.second.pref = self.second.widget.get_pref_size(context, data);
+
+        let mut pref = self.first.pref;
+        let divider = PrefSize::fixed(self.size_of(DIVIDER, 0.0));
+        match self.orientation {
+            Orientation::Horizontal => {
+                pref.row(divider);
+                pref.row(self.second.pref);
+            }
+            Orientation::Vertical => {
+                pref.column(divider);
+                pref.column(self.second.pref);
+            }
+        }
+        pref
+    }
+
+    fn layout(&mut self, size: Size, mut context: WidgetContext, data: &f64) {
+        self.size = size;
+        let first = self.first_length(*data);
+        let second = (self.along(size) - DIVIDER - first).max(0.0);
+        let across = self.across(size);
+
+        let sizes = [
+            (Vec2::ZERO, self.size_of(first, across)),
+            (self.vec_of(first + DIVIDER), self.size_of(second, across)),
+        ];
+        for (pane, (offset, size)) in [&mut self.first, &mut self.second].iter_mut().zip(sizes.iter()) {
+            pane.offset = *offset;
+            if pane.size != *size {
+                pane.size = *size;
+                pane.widget.lifecycle(&Lifecycle::SizeChanged(*size), context.id());
+            }
+            pane.widget.layout(*size, context.id(), data);
+        }
+    }
+
+    fn build(&mut self, mut context: WidgetContext) {
+        self.first.widget.build(context.id());
+        self.second.widget.build(context);
+    }
+
+    fn update(&mut self, new: &f64, old: Option<&f64>) -> Change {
+        let change = self.first.widget.update(new, old).merge(self.second.widget.update(new, old));
+        if old != Some(new) {
+            Change::Bounds
+        } else {
+            change
+        }
+    }
+
+    fn traverse_focus(&mut self, mut context: WidgetContext) -> bool {
+        if self.focus != Some(Part::Second) {
+            if self.first.widget.traverse_focus(context.id()) {
+                self.focus = Some(Part::First);
+                return true;
+            }
+            self.focus = Some(Part::Second);
+        }
+        if self.second.widget.traverse_focus(context) {
+            return true;
+        }
+        self.focus = None;
+        false
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        //The children get their SizeChanged from layout
+        if event.is_broadcast() {
+            self.first.widget.lifecycle(event, context.id());
+            self.second.widget.lifecycle(event, context);
+        }
+    }
+}
@@ -0,0 +1,756 @@
+use crate::widgets::Widget;
+use crate::widgets::scrollbar::{self, ThumbDrag};
+use crate::widgets::capture::captured_event;
+use crate::widgets::style::{StyleAtlas, get_style};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use crate::widgets::drag::{DRAG_THRESHOLD, default_preview};
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Point, Line};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::{KeyCode, MouseEvent, MouseButton, Counter};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+
+/// Items shown by a VirtualList, accessed by index
+pub trait ListSource: Clone + 'static {
+    type Item: Clone + 'static;
+
+    fn len(&self) -> usize;
+    fn item(&self, index: usize) -> &Self::Item;
+    fn item_mut(&mut self, index: usize) -> &mut Self::Item;
+}
+
+/// Items which can be moved by dragging them in a reorderable VirtualList
+pub trait ReorderableSource: ListSource {
+    fn insert(&mut self, index: usize, item: Self::Item);
+    fn remove(&mut self, index: usize) -> Self::Item;
+}
+
+impl<T: Clone + 'static> ListSource for Vec<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+    fn item(&self, index: usize) -> &T {
+        &self[index]
+    }
+    fn item_mut(&mut self, index: usize) -> &mut T {
+        &mut self[index]
+    }
+}
+
+impl<T: Clone + 'static> ReorderableSource for Vec<T> {
+    fn insert(&mut self, index: usize, item: T) {
+        Vec::insert(self, index, item)
+    }
+    fn remove(&mut self, index: usize) -> T {
+        Vec::remove(self, index)
+    }
+}
+
+impl<T: Clone + 'static> ListSource for VecDeque<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+    fn item(&self, index: usize) -> &T {
+        &self[index]
+    }
+    fn item_mut(&mut self, index: usize) -> &mut T {
+        &mut self[index]
+    }
+}
+
+impl<T: Clone + 'static> ReorderableSource for VecDeque<T> {
+    fn insert(&mut self, index: usize, item: T) {
+        VecDeque::insert(self, index, item)
+    }
+    fn remove(&mut self, index: usize) -> T {
+        VecDeque::remove(self, index).unwrap()
+    }
+}
+
+/// The items of a list and the index of the selected one
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListData<S> {
+    pub items: S,
+    pub selected: Option<usize>,
+}
+
+impl<S: ListSource> ListData<S> {
+    pub fn new(items: S) -> Self {
+        ListData {
+            items,
+            selected: None,
+        }
+    }
+    pub fn selected_item(&self) -> Option<&S::Item> {
+        self.selected.filter(|index|*index < self.items.len()).map(|index|self.items.item(index))
+    }
+}
+
+/// The operations of a ReorderableSource, captured by VirtualList::reorderable
+struct Reorder<S: ListSource> {
+    insert: fn(&mut S, usize, S::Item),
+    remove: fn(&mut S, usize) -> S::Item,
+}
+
+impl<S: ListSource> Clone for Reorder<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: ListSource> Copy for Reorder<S> {}
+
+impl<S: ListSource> Reorder<S> {
+    /// Inserts the item and selects it
+    fn insert(&self, list: &mut ListData<S>, index: usize, item: S::Item) {
+        (self.insert)(&mut list.items, index, item);
+        list.selected = Some(index);
+    }
+    /// Removes the item, the selection stays on the same item
+    fn remove(&self, list: &mut ListData<S>, index: usize) -> S::Item {
+        list.selected = match list.selected {
+            Some(selected) if selected == index => None,
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+        (self.remove)(&mut list.items, index)
+    }
+}
+
+/// The payload of a row dragged out of a reorderable VirtualList
+/// Other reorderable lists with the same item type accept it, the item is removed from its list
+/// after it was dropped.
+#[derive(Clone, Debug)]
+pub struct DraggedRow<I> {
+    list: u64,
+    pub index: usize,
+    pub item: I,
+}
+
+/// The height of the rows of a VirtualList
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RowHeight {
+    /// Every row has the same height
+    Fixed(f64),
+    /// Rows are measured by their min height once they are shown, until then the estimate is used
+    Estimated(f64),
+}
+
+/// The heights of all rows, only measured rows are stored
+struct Heights {
+    estimate: f64,
+    fixed: bool,
+    measured: BTreeMap<usize, f64>,
+    /// the number of rows the measured heights belong to
+    len: usize,
+}
+
+impl Heights {
+    fn new(height: RowHeight) -> Self {
+        let (estimate, fixed) = match height {
+            RowHeight::Fixed(height) => (height, true),
+            RowHeight::Estimated(height) => (height, false),
+        };
+        Heights {
+            estimate: estimate.max(1.0),
+            fixed,
+            measured: BTreeMap::new(),
+            len: 0,
+        }
+    }
+    fn measure(&mut self, index: usize, height: f64) {
+        if !self.fixed {
+            self.measured.insert(index, height);
+        }
+    }
+    /// Moves the heights of the following rows down by one row
+    fn insert(&mut self, index: usize) {
+        let moved = self.measured.split_off(&index);
+        self.measured.extend(moved.into_iter().map(|(row, height)|(row + 1, height)));
+        self.len += 1;
+    }
+    /// Forgets the height of the row and moves the following rows up
+    fn remove(&mut self, index: usize) {
+        let moved = self.measured.split_off(&index);
+        self.measured.extend(moved.into_iter().skip_while(|(row, _)|*row == index).map(|(row, height)|(row - 1, height)));
+        self.len = self.len.saturating_sub(1);
+    }
+    /// Forgets all heights if the rows changed without insert or remove, since they can't be
+    /// assigned to their rows anymore
+    fn sync(&mut self, len: usize) {
+        if len != self.len {
+            self.measured.clear();
+            self.len = len;
+        }
+    }
+    fn height(&self, index: usize) -> f64 {
+        self.measured.get(&index).copied().unwrap_or(self.estimate)
+    }
+    /// Returns the position of the top edge of the row
+    fn top(&self, index: usize) -> f64 {
+        let correction: f64 = self.measured.range(..index).map(|(_, height)|height - self.estimate).sum();
+        index as f64 * self.estimate + correction
+    }
+    /// Returns the row at the position
+    fn index_at(&self, y: f64, len: usize) -> usize {
+        let mut top = 0.0;
+        let mut next = 0;
+        for (&index, &height) in self.measured.range(..len) {
+            let gap = (index - next) as f64 * self.estimate;
+            if y < top + gap {
+                return next + ((y - top) / self.estimate) as usize;
+            }
+            top += gap;
+            if y < top + height {
+                return index;
+            }
+            top += height;
+            next = index + 1;
+        }
+        (next + ((y - top).max(0.0) / self.estimate) as usize).min(len.saturating_sub(1))
+    }
+}
+
+struct Row<I> {
+    index: usize,
+    widget: Box<dyn Widget<I>>,
+    y: f64,
+    size: Size,
+}
+
+/// A scrollable list, which only creates Widgets for the visible rows
+///
+/// Rows scrolled out of view are recycled for the rows scrolled into view, they get the new item
+/// by update with None as the old value. Recycled rows receive Lifecycle::Hidden and Shown. The
+/// arrow keys, Home, End, PageUp and PageDown move the selection while the list is focused.
+pub struct VirtualList<S: ListSource> {
+    factory: Box<dyn Fn() -> Box<dyn Widget<S::Item>>>,
+    rows: Vec<Row<S::Item>>,
+    spare: Vec<Box<dyn Widget<S::Item>>>,
+    heights: Heights,
+    style: Arc<StyleAtlas>,
+    offset: f64,
+    thumb: ThumbDrag,
+    hovered: Option<usize>,
+    focused: bool,
+    size: Size,
+    /// identifies the list in the payload of a drag
+    id: u64,
+    /// the operations of a reorderable list
+    reorder: Option<Reorder<S>>,
+    /// the row and position of the last MouseDown
+    pressed: Option<(usize, Point)>,
+    /// the row dragged out of this list
+    dragging: Option<usize>,
+    /// the position a dragged row would be inserted at
+    drop_index: Option<usize>,
+    /// the row which consumed the last MouseDown
+    captured: Option<usize>,
+}
+
+impl<S: ListSource> VirtualList<S> {
+    /// The factory creates the Widget of a row, it is called only for visible rows
+    pub fn new<W: Widget<S::Item> + 'static>(height: RowHeight, factory: impl Fn() -> W + 'static) -> Self {
+        VirtualList {
+            factory: Box::new(move||Box::new(factory())),
+            rows: Vec::new(),
+            spare: Vec::new(),
+            heights: Heights::new(height),
+            style: get_style("list_row"),
+            offset: 0.0,
+            thumb: ThumbDrag::new(),
+            hovered: None,
+            focused: false,
+            size: Size::ZERO,
+            id: {
+                static LISTS: Counter = Counter::new();
+                LISTS.next()
+            },
+            reorder: None,
+            pressed: None,
+            dragging: None,
+            drop_index: None,
+            captured: None,
+        }
+    }
+    pub fn with_style(mut self, style: Arc<StyleAtlas>) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn is_reorderable(&self) -> bool {
+        self.reorder.is_some()
+    }
+
+    fn total_height(&self, len: usize) -> f64 {
+        self.heights.top(len)
+    }
+
+    fn clamp_offset(&mut self, len: usize) {
+        let max = (self.total_height(len) - self.size.height).max(0.0);
+        self.offset = self.offset.min(max).max(0.0);
+    }
+
+    /// Scrolls until the row is completely visible
+    fn scroll_to(&mut self, index: usize) {
+        let top = self.heights.top(index);
+        let bottom = top + self.heights.height(index);
+        if top < self.offset {
+            self.offset = top;
+        } else if bottom > self.offset + self.size.height {
+            self.offset = bottom - self.size.height;
+        }
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let y = pos.y + self.offset;
+        self.rows.iter()
+            .position(|row|y >= row.y && y < row.y + row.size.height)
+    }
+
+    fn item_key<'b>(data: &'b mut Key<ListData<S>>, index: usize) -> Key<'b, S::Item> {
+        Key::new(data.value.items.item_mut(index), &mut *data.changed_flag)
+    }
+
+    /// Returns a Widget for the row, recycled if possible
+    fn instantiate(&mut self, index: usize, mut context: WidgetContext, item: &S::Item) -> Row<S::Item> {
+        let mut widget = match self.spare.pop() {
+            Some(mut widget) => {
+                widget.lifecycle(&Lifecycle::Shown, context.id());
+                widget
+            }
+            None => {
+                let mut widget = (self.factory)();
+                widget.build(context.id());
+                widget.lifecycle(&Lifecycle::WidgetAdded, context.id());
+                widget
+            }
+        };
+        let _ = widget.update(item, None);
+        Row {
+            index,
+            widget,
+            y: 0.0,
+            size: Size::ZERO,
+        }
+    }
+
+    /// Creates the visible rows and lays them out, rows out of view are moved to the spare ones
+    fn arrange(&mut self, mut context: WidgetContext, data: &ListData<S>) {
+        let len = data.items.len();
+        self.heights.sync(len);
+        self.clamp_offset(len);
+
+        let first = self.heights.index_at(self.offset, len);
+        let bottom = self.offset + self.size.height;
+
+        let mut old = Vec::new();
+        for row in std::mem::replace(&mut self.rows, Vec::new()) {
+            if row.index < first || row.index >= len || self.heights.top(row.index) >= bottom {
+                self.hide(row, context.id());
+            } else {
+                old.push(row);
+            }
+        }
+
+        let mut y = self.heights.top(first);
+        let mut index = first;
+        while index < len && y < bottom {
+            let item = data.items.item(index);
+            let mut row = match old.iter().position(|row|row.index == index) {
+                Some(position) => old.swap_remove(position),
+                None => self.instantiate(index, context.id(), item),
+            };
+            if !self.heights.fixed {
+                let pref = row.widget.get_pref_size(context.id(), item);
+                self.heights.measure(index, pref.min.height.max(1.0));
+            }
+            let size = Size::new(self.size.width, self.heights.height(index));
+            row.y = y;
+            if row.size != size {
+                row.size = size;
+                row.widget.lifecycle(&Lifecycle::SizeChanged(size), context.id());
+            }
+            row.widget.layout(size, context.id(), item);
+            self.rows.push(row);
+            y += size.height;
+            index += 1;
+        }
+        for row in old {
+            self.hide(row, context.id());
+        }
+
+        //Measured rows can move the end of the list
+        self.clamp_offset(len);
+        if self.hovered.map_or(false, |hovered|!self.rows.iter().any(|row|row.index == hovered)) {
+            self.hovered = None;
+        }
+    }
+
+    fn hide(&mut self, mut row: Row<S::Item>, context: WidgetContext) {
+        row.widget.lifecycle(&Lifecycle::Hidden, context);
+        self.spare.push(row.widget);
+    }
+
+    /// Moves the mouse focus to the row, sending MouseExit and MouseEnter
+    fn hover(&mut self, row: Option<usize>, mouse: Option<&MouseEvent>, context: &mut WidgetContext, data: &mut Key<ListData<S>>) -> EventResponse {
+        let index = row.map(|row|self.rows[row].index);
+        if index == self.hovered {
+            return EventResponse::NONE;
+        }
+        let mut response = EventResponse::changed(self.size, false);
+        let offset = self.offset;
+        if let Some(old) = self.hovered.take() {
+            if let Some(row) = self.rows.iter_mut().find(|row|row.index == old) {
+                let shift = Vec2::new(0.0, row.y - offset);
+                response = response.merge(row.widget.handle_event(Event::MouseExit, context.id(), Self::item_key(data, old)).shift(shift));
+            }
+        }
+        if let (Some(row), Some(mouse)) = (row, mouse) {
+            let row = &mut self.rows[row];
+            let shift = Vec2::new(0.0, row.y - offset);
+            let mut mouse = mouse.clone();
+            mouse.pos -= shift;
+            response = response.merge(row.widget.handle_event(Event::MouseEnter(mouse), context.id(), Self::item_key(data, row.index)).shift(shift));
+        }
+        self.hovered = index;
+        response
+    }
+
+    fn select(&mut self, mut context: WidgetContext, data: &mut Key<ListData<S>>, index: usize) -> EventResponse {
+        let len = data.items.len();
+        if len == 0 {
+            return EventResponse::DELETED;
+        }
+        let index = index.min(len - 1);
+        if data.selected != Some(index) {
+            data.change(|list|list.selected = Some(index));
+        }
+        self.scroll_to(index);
+        self.arrange(context.id(), &**data);
+        EventResponse::changed(self.size, true)
+    }
+
+    /// Returns the index a row dropped at the position would get
+    fn drop_index_at(&self, pos: Point, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        let y = pos.y + self.offset;
+        let index = self.heights.index_at(y, len);
+        if y > self.heights.top(index) + self.heights.height(index) / 2.0 {
+            index + 1
+        } else {
+            index
+        }
+    }
+
+    fn start_drag(&mut self, context: &mut WidgetContext, data: &ListData<S>, index: usize) {
+        self.pressed = None;
+        if index >= data.items.len() {
+            return;
+        }
+        let payload = DraggedRow {
+            list: self.id,
+            index,
+            item: data.items.item(index).clone(),
+        };
+        let size = Size::new(self.size.width, self.heights.height(index));
+        context.start_drag(payload, size, default_preview());
+        self.dragging = Some(index);
+    }
+
+    /// Shows where a dragged row would be inserted
+    fn drag_over(&mut self, context: &WidgetContext, pos: Option<Point>, len: usize) -> EventResponse {
+        let drop_index = pos
+            .filter(|_|self.is_reorderable() && context.drag_payload::<DraggedRow<S::Item>>().is_some())
+            .map(|pos|self.drop_index_at(pos, len));
+        if drop_index == self.drop_index {
+            return EventResponse::NONE;
+        }
+        self.drop_index = drop_index;
+        EventResponse::changed(self.size, false)
+    }
+
+    fn drop(&mut self, context: &mut WidgetContext, data: &mut Key<ListData<S>>, index: usize) -> EventResponse {
+        let reorder = match self.reorder {
+            Some(reorder) => reorder,
+            None => return EventResponse::NONE,
+        };
+        let row = match context.accept_drop::<DraggedRow<S::Item>>() {
+            Some(row) => row,
+            None => return EventResponse::NONE,
+        };
+        if row.list == self.id {
+            //The row moved inside of this list, it isn't removed after the drag
+            self.dragging = None;
+            let to = if index > row.index {index - 1} else {index};
+            self.heights.remove(row.index);
+            self.heights.insert(to);
+            data.change(|list|{
+                let item = reorder.remove(list, row.index);
+                reorder.insert(list, to, item);
+            });
+        } else {
+            let index = index.min(data.items.len());
+            self.heights.insert(index);
+            data.change(move|list|reorder.insert(list, index, row.item));
+        }
+        self.arrange(context.id(), &**data);
+        EventResponse::changed(self.size, true)
+    }
+
+    /// Sends the mouse event to the row which captured the mouse
+    fn captured_event(&mut self, row: usize, event: Event, context: WidgetContext, mut data: Key<ListData<S>>) -> EventResponse {
+        if let Event::MouseUp(_) = event {
+            self.captured = None;
+        }
+        let shift = Vec2::new(0.0, self.rows[row].y - self.offset);
+        let row = &mut self.rows[row];
+        let (response, exited) = captured_event(&mut *row.widget, shift, row.size, event, context, Self::item_key(&mut data, row.index));
+        if exited {
+            self.hovered = None;
+            return response.merge(EventResponse::changed(self.size, false));
+        }
+        response
+    }
+
+    fn page_rows(&self) -> usize {
+        ((self.size.height / self.heights.estimate) as usize).max(1)
+    }
+}
+
+impl<S: ReorderableSource> VirtualList<S> {
+    /// Rows can be dragged to another position or into another reorderable list
+    pub fn reorderable(mut self) -> Self {
+        self.reorder = Some(Reorder {
+            insert: S::insert,
+            remove: S::remove,
+        });
+        self
+    }
+}
+
+impl<S: ListSource> Widget<ListData<S>> for VirtualList<S> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &ListData<S>) {
+        for row in self.rows.iter_mut() {
+            let shift = Vec2::new(0.0, row.y - self.offset);
+            let row_dirty = (dirty_rect - shift).intersect(row.size.to_rect());
+            if row_dirty.area() <= 0.0 {
+                continue;
+            }
+            let style = if data.selected == Some(row.index) {
+                self.style.active.as_ref()
+            } else if self.hovered == Some(row.index) {
+                self.style.hovered.as_ref()
+            } else {
+                None
+            }.unwrap_or(&self.style.idle);
+            let item = data.items.item(row.index);
+            let _ = painter.with_save(|painter|{
+                painter.clip(size.to_rect());
+                painter.transform(Affine::translate(shift));
+                style.draw(painter, row.size);
+                row.widget.draw(painter, row.size, row_dirty, context.id(), item);
+                Ok(())
+            });
+        }
+
+        if let Some(index) = self.drop_index {
+            let y = self.heights.top(index) - self.offset;
+            let brush = painter.solid_brush(Color::rgb8(60, 110, 200));
+            painter.stroke(Line::new((0.0, y), (size.width, y)), &brush, 2.0);
+        }
+
+        scrollbar::draw(painter, size.to_rect(), self.total_height(data.items.len()), self.offset);
+    }
+
+    fn handle_event(&mut self, mut event: Event, mut context: WidgetContext, mut data: Key<ListData<S>>) -> EventResponse {
+        if let Some(mouse) = event.mouse_event().cloned() {
+            let total = self.total_height(data.items.len());
+            if let Some(offset) = self.thumb.handle_event(&event, self.size.to_rect(), total, self.offset) {
+                self.offset = offset;
+                self.arrange(context.id(), &*data);
+                return EventResponse::changed(self.size, true);
+            }
+            if let Event::MouseDown(_) = event {
+                self.captured = None;
+            }
+            if context.is_pointer_captured() {
+                let captured = self.captured.and_then(|index|self.rows.iter().position(|row|row.index == index));
+                if let Some(row) = captured {
+                    return self.captured_event(row, event, context, data);
+                }
+            }
+            let row = self.row_at(mouse.pos);
+            let mut response = self.hover(row, Some(&mouse), &mut context, &mut data);
+            if self.is_reorderable() {
+                let len = data.items.len();
+                match &event {
+                    Event::MouseDown(_) if mouse.button == MouseButton::Left => {
+                        self.pressed = row.map(|row|(self.rows[row].index, mouse.pos));
+                    }
+                    Event::MouseMove(_) => {
+                        if let Some((index, pressed)) = self.pressed {
+                            if (mouse.pos - pressed).hypot() > DRAG_THRESHOLD {
+                                self.start_drag(&mut context, &*data, index);
+                            }
+                        }
+                    }
+                    Event::MouseUp(_) => {
+                        self.pressed = None;
+                        self.drop_index = None;
+                        if context.drag_payload::<DraggedRow<S::Item>>().is_some() {
+                            let index = self.drop_index_at(mouse.pos, len);
+                            return response.merge(self.drop(&mut context, &mut data, index));
+                        }
+                    }
+                    _ => {}
+                }
+                response = response.merge(self.drag_over(&context, Some(mouse.pos), len));
+            }
+            if let Some(row) = row {
+                if let Event::MouseEnter(_) = event {
+                    //Already entered
+                } else {
+                    let index = self.rows[row].index;
+                    let shift = Vec2::new(0.0, self.rows[row].y - self.offset);
+                    let size = self.rows[row].size;
+                    event.shift(shift, size);
+                    let row_response = self.rows[row].widget.handle_event(event.clone(), context.id(), Self::item_key(&mut data, index));
+                    response = response.merge(row_response.shift(shift));
+                    if let Event::MouseDown(_) = event {
+                        if !row_response.is_present() {
+                            self.captured = Some(index);
+                        }
+                    }
+                    if row_response.is_present() {
+                        if let Event::MouseDown(_) = event {
+                            response = response.merge(self.select(context.id(), &mut data, index));
+                        }
+                    } else {
+                        return response;
+                    }
+                }
+            }
+            if let Event::Wheel(_) = event {
+                let offset = self.offset;
+                self.offset += mouse.wheel_delta.y;
+                self.arrange(context.id(), &*data);
+                if (self.offset - offset).abs() > std::f64::EPSILON {
+                    //The rows moved below the pointer
+                    let row = self.row_at(mouse.pos);
+                    let hover = self.hover(row, Some(&mouse), &mut context, &mut data);
+                    response = response.merge(hover).merge(EventResponse::changed(self.size, true));
+                }
+            }
+            return response;
+        }
+
+        match event {
+            Event::MouseExit => {
+                if let Some((index, _)) = self.pressed {
+                    //The mouse left the list faster than the threshold
+                    self.start_drag(&mut context, &*data, index);
+                }
+                let len = data.items.len();
+                self.drag_over(&context, None, len).merge(self.hover(None, None, &mut context, &mut data))
+            }
+            Event::DragEnded(accepted) if self.dragging.is_some() => {
+                let index = self.dragging.take().unwrap();
+                if let (true, Some(reorder)) = (accepted && index < data.items.len(), self.reorder) {
+                    self.heights.remove(index);
+                    data.change(|list|{
+                        reorder.remove(list, index);
+                    });
+                    self.arrange(context.id(), &*data);
+                    EventResponse::changed(self.size, false)
+                } else {
+                    EventResponse::NONE
+                }
+            }
+            Event::KeyDown(key_event) if self.focused => {
+                let len = data.items.len();
+                let selected = data.selected;
+                let index = match key_event.key_code {
+                    KeyCode::ArrowDown => selected.map_or(0, |index|index + 1),
+                    KeyCode::ArrowUp => selected.map_or(0, |index|index.saturating_sub(1)),
+                    KeyCode::PageDown => selected.map_or(0, |index|index + self.page_rows()),
+                    KeyCode::PageUp => selected.map_or(0, |index|index.saturating_sub(self.page_rows())),
+                    KeyCode::Home => 0,
+                    KeyCode::End => len.saturating_sub(1),
+                    _ => return EventResponse::NONE,
+                };
+                self.select(context, &mut data, index)
+            }
+            event if event.is_broadcast() => {
+                let offset = self.offset;
+                let mut response = EventResponse::NONE;
+                for row in self.rows.iter_mut() {
+                    let row_response = row.widget.handle_event(event.clone(), context.id(), Self::item_key(&mut data, row.index));
+                    response = response.merge(row_response.shift(Vec2::new(0.0, row.y - offset)));
+                }
+                response
+            }
+            _ => EventResponse::NONE,
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &ListData<S>) -> PrefSize {
+        PrefSize::flexible(Size::new(100.0, self.heights.estimate * 3.0))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &ListData<S>) {
+        self.size = size;
+        self.arrange(context, data);
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &ListData<S>, old: Option<&ListData<S>>) -> Change {
+        let len = new.items.len();
+        let mut change = Change::None;
+        for row in self.rows.iter_mut() {
+            if row.index < len {
+                let old_item = old.filter(|old|row.index < old.items.len()).map(|old|old.items.item(row.index));
+                let row_change = row.widget.update(new.items.item(row.index), old_item);
+                change = change.merge(row_change.shift(Vec2::new(0.0, row.y - self.offset)));
+            }
+        }
+        if old.map_or(true, |old|old.items.len() != len) {
+            return Change::Bounds;
+        }
+        if old.map(|old|old.selected) != Some(new.selected) {
+            change = change.merge(Change::Content(self.size.to_rect()));
+        }
+        change
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.focused = !self.focused;
+        self.focused
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        //Spare rows are hidden already, they are only told about their removal
+        if event.is_broadcast() {
+            for row in self.rows.iter_mut() {
+                row.widget.lifecycle(event, context.id());
+            }
+            if *event == Lifecycle::WidgetRemoved {
+                for widget in self.spare.iter_mut() {
+                    widget.lifecycle(event, context.id());
+                }
+            }
+        }
+    }
+}
@@ -8,10 +8,40 @@ mod state;
 mod lens;
 mod widget;
 mod button;
+mod toggle;
+mod radio;
+mod slider;
+mod stepper;
+mod dropdown;
+mod progress;
+mod image;
+mod canvas;
+mod tabs;
+mod list;
+mod table;
+mod tree;
+mod tooltip;
+mod drag;
+mod scrollbar;
+mod capture;
 
 pub use widget::{Widget, WidgetCompose, IntoWidget};
 pub use state::{StateWrapper, StateWidget, state};
-pub use button::{button};
+pub use button::{button, command_button};
 pub use spacer::Spacer;
+pub use toggle::{Checkbox, Switch};
+pub use radio::RadioGroup;
+pub use slider::{Slider, Orientation};
+pub use stepper::{stepper, Steppable};
+pub use dropdown::Dropdown;
+pub use progress::{ProgressBar, Spinner};
+pub use canvas::Canvas;
+pub use tabs::Tabs;
+pub use list::{VirtualList, ListSource, ReorderableSource, ListData, RowHeight, DraggedRow};
+pub use table::{Table, TableData, Column, ColumnWidth, Sort};
+pub use tree::{TreeView, TreeData, TreeNode};
+pub use tooltip::{Tooltip, TooltipContent};
+pub use drag::{DragSource, DropTarget};
+pub use image::{Image, DynImage, ImageSource, ImageData, FitMode, load_image, clear_image_cache};
 
-pub(crate) use state::StateWidgetImpl;
\ No newline at end of file
+pub(crate) use state::{StateWidgetImpl, RemovedStateWidget};
\ No newline at end of file
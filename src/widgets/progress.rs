@@ -0,0 +1,180 @@
+use crate::widgets::Widget;
+use crate::widgets::text::Label;
+use crate::widgets::style::{Style, get_style};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::{PrefSize, GROW_NORMAL, DONT_GROW};
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Circle, Point};
+use druid_shell::piet::{Piet, RenderContext};
+use std::f64::consts::PI;
+
+/// Shows a value between 0.0 and 1.0 as a partially filled track
+pub struct ProgressBar {
+    track: Style,
+    fill: Style,
+    label: Option<Label>,
+    /// the value at the last update, States pass None as old value
+    value: Option<f64>,
+    size: Size,
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        ProgressBar::with_styles(get_style("progress_track").idle.clone(),
+                                 get_style("progress_fill").idle.clone())
+    }
+    pub fn with_styles(track: Style, fill: Style) -> Self {
+        ProgressBar {
+            track,
+            fill,
+            label: None,
+            value: None,
+            size: Size::ZERO,
+        }
+    }
+    /// Shows the progress in percent in the middle of the bar
+    pub fn with_label(mut self) -> Self {
+        self.label = Some(Label::new("", None));
+        self
+    }
+}
+
+impl Widget<f64> for ProgressBar {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &f64) {
+        self.track.draw(painter, size);
+
+        let progress = data.max(0.0).min(1.0);
+        let fill = Size::new(size.width * progress, size.height);
+        if fill.width >= 1.0 {
+            let style = &self.fill;
+            let _ = painter.with_save(|painter|{
+                painter.clip(fill.to_rect());
+                style.draw(painter, size);
+                Ok(())
+            });
+        }
+
+        if let Some(label) = self.label.as_mut() {
+            let x = ((size.width - label.width()) / 2.0 - 4.0).max(0.0);
+            let y = ((size.height - 17.0) / 2.0).max(0.0);
+            painter.transform(Affine::translate(Vec2::new(x, y)));
+            label.draw(painter, size, dirty_rect, context, &());
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<f64>) -> EventResponse {
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &f64) -> PrefSize {
+        let height = if self.label.is_some() {17.0} else {8.0};
+        PrefSize::new((40.0, height), (200.0, height), (GROW_NORMAL, DONT_GROW))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &f64) {
+        self.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &f64, old: Option<&f64>) -> Change {
+        if self.value == Some(*new) {
+            return Change::None;
+        }
+        self.value = Some(*new);
+        if let Some(label) = self.label.as_mut() {
+            *label.set_text() = format!("{:.0}%", new.max(0.0).min(1.0) * 100.0);
+            label.recalc_text();
+        }
+        Change::Content(self.size.to_rect())
+    }
+}
+
+const DOTS: usize = 8;
+/// Rotations per second
+const SPEED: f64 = 1.0;
+
+/// An animated circle of dots for work of unknown duration
+/// The Spinner animates while it is shown.
+pub struct Spinner {
+    style: Style,
+    angle: f64,
+    running: bool,
+    size: Size,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Spinner::with_style(get_style("progress_fill").idle.clone())
+    }
+    /// The dots are filled with the background of the style
+    pub fn with_style(style: Style) -> Self {
+        Spinner {
+            style,
+            angle: 0.0,
+            running: false,
+            size: Size::ZERO,
+        }
+    }
+}
+
+impl<T: Clone> Widget<T> for Spinner {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        let background = match self.style.background.as_ref() {
+            Some(background) => background,
+            None => return,
+        };
+        let radius = size.width.min(size.height) / 2.0;
+        let center = Point::new(size.width / 2.0, size.height / 2.0);
+        //The dot at the current angle is the biggest, the following ones shrink
+        let step = 2.0 * PI / DOTS as f64;
+        let lead = (self.angle / step).floor();
+        for dot in 0..DOTS {
+            let angle = (lead - dot as f64) * step;
+            let dot_radius = radius * 0.18 * (1.0 - dot as f64 / DOTS as f64) + 0.5;
+            let position = center + Vec2::from_angle(angle) * (radius - radius * 0.2);
+            background.draw(painter, Circle::new(position, dot_radius));
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, data: Key<T>) -> EventResponse {
+        if let Event::AnimFrame(interval) = event {
+            if self.running {
+                self.angle = (self.angle + interval.as_secs_f64() * SPEED * 2.0 * PI) % (2.0 * PI);
+                context.request_anim_frame();
+                return EventResponse::changed(self.size, false);
+            }
+        }
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        PrefSize::fixed(Size::new(24.0, 24.0))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        Change::None
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        match event {
+            Lifecycle::WidgetAdded | Lifecycle::Shown => {
+                if !self.running {
+                    self.running = true;
+                    context.request_anim_frame();
+                }
+            }
+            Lifecycle::Hidden | Lifecycle::WidgetRemoved => {
+                self.running = false;
+            }
+            _ => {}
+        }
+    }
+}
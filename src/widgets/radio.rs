@@ -0,0 +1,190 @@
+use crate::widgets::Widget;
+use crate::widgets::toggle::Toggle;
+use crate::widgets::style::{StyleAtlas, get_style};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Circle};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::KeyCode;
+use std::sync::Arc;
+
+/// A column of radio buttons, which selects one of its options
+/// The option equal to the current value is checked. Arrow keys move the focus between the
+/// options, Space selects the focused one.
+pub struct RadioGroup<T> {
+    options: Vec<(T, Toggle)>,
+    style: Arc<StyleAtlas>,
+    hovered: Option<usize>,
+    focus: Option<usize>,
+    row_height: f64,
+    size: Size,
+}
+
+impl<T: Clone + PartialEq> RadioGroup<T> {
+    pub fn new() -> Self {
+        RadioGroup::with_style(get_style("radio"))
+    }
+    pub fn with_style(style: Arc<StyleAtlas>) -> Self {
+        RadioGroup {
+            options: Vec::new(),
+            style,
+            hovered: None,
+            focus: None,
+            row_height: 0.0,
+            size: Size::ZERO,
+        }
+    }
+    /// Adds an option, which sets the value to value when selected
+    pub fn option(mut self, value: T, label: impl Into<String>) -> Self {
+        let toggle = Toggle::new(label, self.style.clone(), Size::new(16.0, 16.0));
+        self.options.push((value, toggle));
+        self
+    }
+
+    fn row_at(&self, y: f64) -> Option<usize> {
+        if y < 0.0 || self.row_height <= 0.0 {
+            return None;
+        }
+        let row = (y / self.row_height) as usize;
+        if row < self.options.len() {
+            Some(row)
+        } else {
+            None
+        }
+    }
+
+    fn row_offset(&self, row: usize) -> Vec2 {
+        Vec2::new(0.0, row as f64 * self.row_height)
+    }
+
+    /// Sends the event to the option at row and selects it, if it was activated
+    fn forward(&mut self, row: usize, event: Event, data: &mut Key<T>) -> EventResponse {
+        let (response, activated) = self.options[row].1.handle_event(event);
+        if activated && **data != self.options[row].0 {
+            let value = self.options[row].0.clone();
+            data.change(|selected|*selected = value);
+        }
+        response.shift(self.row_offset(row))
+    }
+}
+
+impl<T: Clone + PartialEq> Widget<T> for RadioGroup<T> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &T) {
+        let row_size = Size::new(size.width, self.row_height);
+        for (row, (value, toggle)) in self.options.iter_mut().enumerate() {
+            let offset = Vec2::new(0.0, row as f64 * row_size.height);
+            let row_dirty = (dirty_rect - offset).intersect(row_size.to_rect());
+            if row_dirty.area() <= 0.0 {
+                continue;
+            }
+            let checked = *value == *data;
+            let context = context.id();
+            let _ = painter.with_save(|painter|{
+                painter.transform(Affine::translate(offset));
+                toggle.draw(painter, row_size, row_dirty, context, |painter, size|{
+                    if checked {
+                        let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+                        painter.fill(Circle::new((size.width / 2.0, size.height / 2.0), 4.0), &brush);
+                    }
+                });
+                Ok(())
+            });
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<T>) -> EventResponse {
+        match &event {
+            Event::KeyDown(key_event) => {
+                let focus = match self.focus {
+                    Some(focus) => focus,
+                    None => return EventResponse::NONE,
+                };
+                let new_focus = match key_event.key_code {
+                    KeyCode::ArrowUp if focus > 0 => focus - 1,
+                    KeyCode::ArrowDown if focus + 1 < self.options.len() => focus + 1,
+                    _ => return self.forward(focus, event, &mut data),
+                };
+                self.options[focus].1.traverse_focus();
+                self.options[new_focus].1.traverse_focus();
+                self.focus = Some(new_focus);
+                EventResponse::changed(self.size, true)
+            }
+            Event::MouseExit => {
+                match self.hovered.take() {
+                    Some(row) => self.forward(row, event, &mut data),
+                    None => EventResponse::NONE,
+                }
+            }
+            _ => {
+                let mouse = match event.mouse_event() {
+                    Some(mouse) => mouse.clone(),
+                    None => return EventResponse::NONE,
+                };
+                let row = self.row_at(mouse.pos.y);
+                let mut response = EventResponse::NONE;
+                if row != self.hovered {
+                    if let Some(old) = self.hovered.take() {
+                        response = response.merge(self.forward(old, Event::MouseExit, &mut data));
+                    }
+                    if let Some(new) = row {
+                        response = response.merge(self.forward(new, Event::MouseEnter(mouse.clone()), &mut data));
+                    }
+                    self.hovered = row;
+                }
+                match (row, event) {
+                    (_, Event::MouseEnter(_)) | (None, _) => response,
+                    (Some(row), event) => response.merge(self.forward(row, event, &mut data)),
+                }
+            }
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        let mut width: f64 = 0.0;
+        let mut height: f64 = 0.0;
+        for (_, toggle) in self.options.iter() {
+            let pref = toggle.pref_size();
+            width = width.max(pref.max.width);
+            height = height.max(pref.max.height);
+        }
+        self.row_height = height;
+        PrefSize::fixed(Size::new(width, height * self.options.len() as f64))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+        if !self.options.is_empty() {
+            self.row_height = size.height / self.options.len() as f64;
+        }
+        let row_size = Size::new(size.width, self.row_height);
+        for (_, toggle) in self.options.iter_mut() {
+            toggle.layout(row_size);
+        }
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        for (value, toggle) in self.options.iter_mut() {
+            toggle.set_checked(*value == *new);
+        }
+        Change::Content(self.size.to_rect())
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        match self.focus.take() {
+            Some(focus) => {
+                self.options[focus].1.traverse_focus();
+                false
+            }
+            None if !self.options.is_empty() => {
+                self.options[0].1.traverse_focus();
+                self.focus = Some(0);
+                true
+            }
+            None => false,
+        }
+    }
+}
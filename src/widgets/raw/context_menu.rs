@@ -0,0 +1,66 @@
+use crate::widgets::Widget;
+use druid_shell::kurbo::{Rect, Size};
+use druid_shell::piet::Piet;
+use druid_shell::MouseButton;
+use crate::state::key::Key;
+use crate::app::size::PrefSize;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::menu::MenuDesc;
+
+/// Shows a context menu when the Widget is right clicked
+/// The commands of the menu are send to the StateRoot of the Widget.
+pub struct ContextMenu<W> {
+    widget: W,
+    menu: MenuDesc,
+}
+
+impl<W> ContextMenu<W> {
+    pub fn new(widget: W, menu: MenuDesc) -> Self {
+        ContextMenu {
+            widget,
+            menu,
+        }
+    }
+}
+
+impl<T: Clone, W: Widget<T>> Widget<T> for ContextMenu<W> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.widget.draw(painter, size, dirty_rect, context, data)
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, data: Key<T>) -> EventResponse {
+        let response = self.widget.handle_event(event.clone(), context.id(), data);
+        if let Event::MouseDown(mouse) = event {
+            if mouse.button == MouseButton::Right && response.is_present() {
+                context.show_context_menu(self.menu.clone(), context.mouse_pos());
+                return response.merge(EventResponse::DELETED);
+            }
+        }
+        response
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.widget.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.widget.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        self.widget.build(context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.widget.update(new, old)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.widget.traverse_focus(context)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
+}
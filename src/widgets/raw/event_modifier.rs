@@ -3,9 +3,10 @@ use crate::state::key::Key;
 use druid_shell::kurbo::{Rect, Size};
 use druid_shell::piet::Piet;
 use druid_shell::MouseEvent;
-use crate::app::event::{Event, EventResponse, Change};
+use crate::app::event::{Event, EventResponse, Change, Lifecycle};
 use crate::app::widget_graph::WidgetContext;
 use crate::app::size::PrefSize;
+use crate::app::menu::Command;
 
 impl<T: Clone, W: Widget<T>, F: FnMut(Event, &mut W, WidgetContext, Key<T>) -> EventResponse> Widget<T> for EventModifier<W, F> {
     fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
@@ -35,6 +36,10 @@ impl<T: Clone, W: Widget<T>, F: FnMut(Event, &mut W, WidgetContext, Key<T>) -> E
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {
         self.0.traverse_focus(context)
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.0.lifecycle(event, context)
+    }
 }
 
 pub struct EventModifier<W, F> (W, F);
@@ -75,10 +80,49 @@ pub fn event_listener<T: Clone, W: Widget<T>>(widget: W, mut listener: impl FnMu
 pub fn event_filter<T: Clone, W: Widget<T>>(widget: W, mut listener: impl FnMut(Event, Key<T>) -> EventResponse) -> impl Widget<T> {
     EventModifier(widget, move|event: Event, widget: &mut W, context: WidgetContext, mut data: Key<T>|{
         let mut response = listener(event.clone(), data.id());
-        if response.is_present() {
+        if response.is_present() || event.is_broadcast() {
             response = response.merge(widget.handle_event(event, context, data))
         }
         response
     })
 }
 
+/// Calls the handler when the command is dispatched to the Widget
+pub fn command_listener<T: Clone, W: Widget<T>>(widget: W, command: Command, mut handler: impl FnMut(Key<T>)) -> impl Widget<T> {
+    EventModifier(widget, move|event: Event, widget: &mut W, context: WidgetContext, data: Key<T>|{
+        match event {
+            Event::Command(received) if received == command => {
+                handler(data);
+                EventResponse::DELETED
+            }
+            event => widget.handle_event(event, context, data),
+        }
+    })
+}
+/// Submits the command when the Widget is clicked
+pub fn click_command<T: Clone, W: Widget<T>>(widget: W, command: Command) -> impl Widget<T> {
+    let mut pressed = false;
+    EventModifier(widget, move|event: Event, widget: &mut W, mut context: WidgetContext, data: Key<T>|{
+        let response = widget.handle_event(event.clone(), context.id(), data);
+        if response.is_present() {
+            match event {
+                Event::MouseDown(_) => {
+                    pressed = true;
+                    return response.merge(EventResponse::DELETED);
+                },
+                Event::MouseExit => {
+                    pressed = false;
+                },
+                Event::MouseUp(_) => {
+                    if pressed {
+                        pressed = false;
+                        context.submit_command(command);
+                        return response.merge(EventResponse::DELETED);
+                    }
+                },
+                _ => {},
+            }
+        }
+        response
+    })
+}
@@ -0,0 +1,70 @@
+use crate::widgets::Widget;
+use druid_shell::kurbo::{Rect, Size};
+use druid_shell::piet::Piet;
+use crate::state::key::Key;
+use crate::app::size::PrefSize;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::command::Keymap;
+use crate::state::{CloneState, State};
+
+/// Key bindings which apply while the focus is inside of the Widget
+/// The commands are submitted like the bindings of the Window, which take precedence. Without a
+/// handler of the Window they are send as Event::Command to the StateRoot of the Widget. Keys of
+/// disabled commands are left to the Widget. Changes of the Keymap State apply to the next key
+/// press.
+pub struct KeyBindings<W> {
+    widget: W,
+    keymap: CloneState<Keymap>,
+}
+
+impl<W> KeyBindings<W> {
+    pub fn new(widget: W, keymap: &CloneState<Keymap>) -> Self {
+        KeyBindings {
+            widget,
+            keymap: keymap.clone(),
+        }
+    }
+}
+
+impl<T: Clone, W: Widget<T>> Widget<T> for KeyBindings<W> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.widget.draw(painter, size, dirty_rect, context, data)
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, data: Key<T>) -> EventResponse {
+        if let Event::KeyDown(key_event) = &event {
+            if let Some(command) = self.keymap.with_fetched_value(|keymap, _|keymap.command(key_event)) {
+                if context.is_command_enabled(command) {
+                    context.submit_local_command(command);
+                    return EventResponse::DELETED;
+                }
+            }
+        }
+        self.widget.handle_event(event, context, data)
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.widget.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.widget.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        self.widget.build(context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.widget.update(new, old)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.widget.traverse_focus(context)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
+}
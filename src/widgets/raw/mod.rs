@@ -3,9 +3,13 @@ mod stacked;
 mod wrapper;
 mod padding;
 mod pref_size;
+mod context_menu;
+mod key_bindings;
 
-pub use event_modifier::{click_listener, event_listener, event_filter, EventModifier};
+pub use event_modifier::{click_listener, event_listener, event_filter, command_listener, click_command, EventModifier};
 pub use stacked::{Stacked, AB, statcked};
 pub use wrapper::{Wrapper, WrapperWidget, WrapperResponse};
 pub use padding::Padding;
 pub use pref_size::PrefSizeWrapper;
+pub use context_menu::ContextMenu;
+pub use key_bindings::KeyBindings;
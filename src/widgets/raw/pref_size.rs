@@ -4,7 +4,7 @@ use druid_shell::piet::Piet;
 use crate::state::key::Key;
 use crate::app::size::PrefSize;
 use crate::app::widget_graph::WidgetContext;
-use crate::app::event::{EventResponse, Event, Change};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
 
 pub struct PrefSizeWrapper<W>{
     widget: W,
@@ -73,4 +73,8 @@ impl<T: Clone, W: Widget<T>> Widget<T> for PrefSizeWrapper<W> {
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {
         self.widget.traverse_focus(context)
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use crate::widgets::Widget;
 use druid_shell::kurbo::{Rect, Size};
 use druid_shell::piet::{Piet, RenderContext};
 use crate::state::key::Key;
-use crate::app::event::{Event, EventResponse, Change};
+use crate::app::event::{Event, EventResponse, Change, Lifecycle};
 use crate::app::widget_graph::WidgetContext;
 use crate::app::size::PrefSize;
 
@@ -39,7 +39,7 @@ impl<T: Clone, A: Widget<T>, B: Widget<T>> Widget<T> for Stacked<A, B> {
 
     fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<T>) -> EventResponse {
         let mut response = self.widget_top.handle_event(event.clone(), context.id(), data.id());
-        if response.is_present() {
+        if response.is_present() || event.is_broadcast() {
             response = response.merge(self.widget_bottom.handle_event(event, context, data));
         }
         response
@@ -81,4 +81,10 @@ impl<T: Clone, A: Widget<T>, B: Widget<T>> Widget<T> for Stacked<A, B> {
         self.focus = None;
         return false;
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        //Both Widgets have the size of the Stacked
+        self.widget_top.lifecycle(event, context.id());
+        self.widget_bottom.lifecycle(event, context);
+    }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use druid_shell::piet::{Piet, RenderContext};
 use druid_shell::kurbo::{Size, Rect, Affine};
 use crate::widgets::Widget;
 use crate::state::key::Key;
-use crate::app::event::{EventResponse, Event, Change};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
 use crate::app::widget_graph::WidgetContext;
 use crate::app::size::PrefSize;
 
@@ -106,7 +106,9 @@ impl<T: Clone, U: Wrapper, W: Widget<T>> Widget<T> for WrapperWidget<U, W> {
 
     fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse{
         let mut client_event = event.clone();
-        let mut response = if client_event.shift(self.shift.to_vec2(), self.content_size) {
+        let inside = client_event.shift(self.shift.to_vec2(), self.content_size);
+        //Parents send mouse events outside of the bounds only to the captured Widget
+        let mut response = if inside || context.is_pointer_captured() {
             self.wrapped_widget.handle_event(client_event, context, data).shift(self.shift.to_vec2())
         } else {
             EventResponse::unchanged(false)
@@ -147,4 +149,13 @@ impl<T: Clone, U: Wrapper, W: Widget<T>> Widget<T> for WrapperWidget<U, W> {
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {
         self.wrapped_widget.traverse_focus(context)
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        //SizeChanged arrives before the layout, content_size still holds the previous size
+        if let Lifecycle::SizeChanged(size) = event {
+            self.wrapped_widget.lifecycle(&Lifecycle::SizeChanged(*size - self.padding), context)
+        } else {
+            self.wrapped_widget.lifecycle(event, context)
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+use crate::app::event::Event;
+use druid_shell::kurbo::Rect;
+use druid_shell::piet::{Piet, RenderContext, Color};
+
+pub(crate) const SCROLLBAR_WIDTH: f64 = 6.0;
+
+fn thumb_height(visible: f64, total: f64) -> f64 {
+    (visible * visible / total).max(16.0)
+}
+
+/// Returns the thumb of the scrollbar at the right edge of the viewport, None if the content fits
+pub(crate) fn thumb(viewport: Rect, total: f64, offset: f64) -> Option<Rect> {
+    let visible = viewport.height();
+    if total <= visible {
+        return None;
+    }
+    let height = thumb_height(visible, total);
+    let y = viewport.y0 + offset / (total - visible) * (visible - height);
+    Some(Rect::new(viewport.x1 - SCROLLBAR_WIDTH, y, viewport.x1, y + height))
+}
+
+/// Draws the scrollbar of the content of the height total, which is scrolled by offset
+pub(crate) fn draw(painter: &mut Piet, viewport: Rect, total: f64, offset: f64) {
+    if let Some(thumb) = thumb(viewport, total, offset) {
+        let brush = painter.solid_brush(Color::rgba8(200, 200, 200, 100));
+        painter.fill(thumb.to_rounded_rect(SCROLLBAR_WIDTH / 2.0), &brush);
+    }
+}
+
+/// Scrolls the content while the thumb is dragged
+pub(crate) struct ThumbDrag {
+    /// the mouse position and the offset when the thumb was pressed
+    start: Option<(f64, f64)>,
+}
+
+impl ThumbDrag {
+    pub(crate) fn new() -> Self {
+        ThumbDrag {
+            start: None,
+        }
+    }
+    /// Returns the new offset if the event belongs to the drag, from the MouseDown on the thumb
+    /// until the MouseUp
+    pub(crate) fn handle_event(&mut self, event: &Event, viewport: Rect, total: f64, offset: f64) -> Option<f64> {
+        match event {
+            Event::MouseDown(mouse) => {
+                let pressed = thumb(viewport, total, offset).map_or(false, |thumb|thumb.contains(mouse.pos));
+                self.start = if pressed {Some((mouse.pos.y, offset))} else {None};
+                self.start.map(|_|offset)
+            }
+            Event::MouseMove(mouse) => {
+                let (start_y, start_offset) = self.start?;
+                let visible = viewport.height();
+                let track = visible - thumb_height(visible, total);
+                if total <= visible || track <= 0.0 {
+                    return Some(offset);
+                }
+                let offset = start_offset + (mouse.pos.y - start_y) / track * (total - visible);
+                Some(offset.min(total - visible).max(0.0))
+            }
+            Event::MouseUp(_) => self.start.take().map(|_|offset),
+            _ => None,
+        }
+    }
+}
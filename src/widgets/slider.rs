@@ -0,0 +1,221 @@
+use crate::widgets::Widget;
+use crate::widgets::style::{StyleAtlas, DynamicStyle, get_style};
+use crate::widgets::raw::Wrapper;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::{PrefSize, GROW_NORMAL, DONT_GROW};
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Point};
+use druid_shell::piet::{Piet, RenderContext};
+use druid_shell::KeyCode;
+use std::sync::Arc;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+const THUMB: f64 = 14.0;
+const TRACK: f64 = 4.0;
+
+/// Selects a value between min and max by dragging a thumb along a track
+/// The arrow keys move the thumb by one step, Home and End to the ends of the range.
+pub struct Slider {
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    orientation: Orientation,
+    track: DynamicStyle,
+    thumb: DynamicStyle,
+    dragging: bool,
+    focused: bool,
+    size: Size,
+}
+
+impl Slider {
+    pub fn new(min: f64, max: f64) -> Self {
+        Slider {
+            min,
+            max: max.max(min),
+            step: None,
+            orientation: Orientation::Horizontal,
+            track: DynamicStyle::new(get_style("slider_track"), false),
+            thumb: DynamicStyle::new(get_style("slider_thumb"), false),
+            dragging: false,
+            focused: false,
+            size: Size::ZERO,
+        }
+    }
+    /// Snaps the value to multiples of step above min
+    pub fn step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = Some(step);
+        }
+        self
+    }
+    pub fn vertical(mut self) -> Self {
+        self.orientation = Orientation::Vertical;
+        self
+    }
+    pub fn track_style(mut self, style: Arc<StyleAtlas>) -> Self {
+        self.track = DynamicStyle::new(style, false);
+        self
+    }
+    pub fn thumb_style(mut self, style: Arc<StyleAtlas>) -> Self {
+        self.thumb = DynamicStyle::new(style, false);
+        self
+    }
+
+    fn length(&self) -> f64 {
+        let length = match self.orientation {
+            Orientation::Horizontal => self.size.width,
+            Orientation::Vertical => self.size.height,
+        };
+        (length - THUMB).max(0.0)
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        let value = match self.step {
+            Some(step) => self.min + ((value - self.min) / step).round() * step,
+            None => value,
+        };
+        value.max(self.min).min(self.max)
+    }
+
+    /// The position of the thumb between 0.0 and 1.0, vertical sliders grow upwards
+    fn fraction(&self, value: f64) -> f64 {
+        if self.max > self.min {
+            ((value - self.min) / (self.max - self.min)).max(0.0).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn value_at(&self, pos: Point) -> f64 {
+        let length = self.length();
+        if length <= 0.0 {
+            return self.min;
+        }
+        let fraction = match self.orientation {
+            Orientation::Horizontal => (pos.x - THUMB / 2.0) / length,
+            Orientation::Vertical => 1.0 - (pos.y - THUMB / 2.0) / length,
+        };
+        self.clamp(self.min + fraction.max(0.0).min(1.0) * (self.max - self.min))
+    }
+
+    fn thumb_offset(&self, value: f64) -> Vec2 {
+        let along = self.fraction(value) * self.length();
+        match self.orientation {
+            Orientation::Horizontal => Vec2::new(along, (self.size.height - THUMB) / 2.0),
+            Orientation::Vertical => Vec2::new((self.size.width - THUMB) / 2.0, self.length() - along),
+        }
+    }
+
+    fn key_step(&self) -> f64 {
+        self.step.unwrap_or((self.max - self.min) / 100.0)
+    }
+
+    fn set(&self, data: &mut Key<f64>, value: f64) {
+        let value = self.clamp(value);
+        if **data != value {
+            data.change(|current|*current = value);
+        }
+    }
+}
+
+impl Widget<f64> for Slider {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &f64) {
+        let (track_offset, track_size) = match self.orientation {
+            Orientation::Horizontal => (Vec2::new(THUMB / 2.0, (size.height - TRACK) / 2.0),
+                                        Size::new(self.length(), TRACK)),
+            Orientation::Vertical => (Vec2::new((size.width - TRACK) / 2.0, THUMB / 2.0),
+                                      Size::new(TRACK, self.length())),
+        };
+        let track = &self.track;
+        let _ = painter.with_save(|painter|{
+            painter.transform(Affine::translate(track_offset));
+            track.draw(painter, track_size, dirty_rect);
+            Ok(())
+        });
+
+        let thumb_offset = self.thumb_offset(*data);
+        let thumb = &self.thumb;
+        let _ = painter.with_save(|painter|{
+            painter.transform(Affine::translate(thumb_offset));
+            thumb.draw(painter, Size::new(THUMB, THUMB), dirty_rect);
+            Ok(())
+        });
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<f64>) -> EventResponse {
+        let styled = self.thumb.handle_event(event.clone()).content_change();
+        let mut consumed = false;
+        match &event {
+            Event::MouseDown(mouse) => {
+                self.dragging = true;
+                let value = self.value_at(mouse.pos);
+                self.set(&mut data, value);
+                consumed = true;
+            }
+            Event::MouseMove(mouse) => {
+                if self.dragging {
+                    let value = self.value_at(mouse.pos);
+                    self.set(&mut data, value);
+                    consumed = true;
+                }
+            }
+            Event::MouseUp(_) => {
+                consumed = self.dragging;
+                self.dragging = false;
+            }
+            Event::MouseExit => {
+                //While dragging this is only send when the button is released outside of the slider
+                self.dragging = false;
+            }
+            Event::KeyDown(key_event) if self.focused => {
+                let value = *data;
+                let new = match key_event.key_code {
+                    KeyCode::ArrowRight | KeyCode::ArrowUp => Some(value + self.key_step()),
+                    KeyCode::ArrowLeft | KeyCode::ArrowDown => Some(value - self.key_step()),
+                    KeyCode::Home => Some(self.min),
+                    KeyCode::End => Some(self.max),
+                    _ => None,
+                };
+                if let Some(new) = new {
+                    self.set(&mut data, new);
+                    consumed = true;
+                }
+            }
+            _ => {}
+        }
+        if styled {
+            EventResponse::changed(self.size, consumed)
+        } else {
+            EventResponse::unchanged(consumed)
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &f64) -> PrefSize {
+        match self.orientation {
+            Orientation::Horizontal => PrefSize::new((60.0, THUMB + 4.0), (200.0, THUMB + 4.0), (GROW_NORMAL, DONT_GROW)),
+            Orientation::Vertical => PrefSize::new((THUMB + 4.0, 60.0), (THUMB + 4.0, 200.0), (DONT_GROW, GROW_NORMAL)),
+        }
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &f64) {
+        self.size = size;
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &f64, old: Option<&f64>) -> Change {
+        Change::Content(self.size.to_rect())
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.focused = !self.focused;
+        self.thumb.set_focused(self.focused);
+        self.focused
+    }
+}
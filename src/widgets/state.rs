@@ -1,11 +1,12 @@
-use crate::state::{StateID, CloneState, State};
+use crate::state::{StateID, State};
 use druid_shell::piet::Piet;
 use crate::widgets::widget::Widget;
 use druid_shell::kurbo::{Size, Rect};
 use crate::state::key::Key;
 use pool_tree::child_unique::ChildUniq;
 use std::mem::replace;
-use crate::app::event::{Change, Event, EventResponse};
+use std::marker::PhantomData;
+use crate::app::event::{Change, Event, EventResponse, Lifecycle};
 use crate::app::widget_graph::{WidgetContext, StateRoot, Env};
 use crate::app::size::PrefSize;
 
@@ -18,6 +19,7 @@ pub trait StateWidget {
     fn layout(&mut self, size: Size, context: WidgetContext);
     fn build(&mut self, context: WidgetContext);
     fn traverse_focus(&mut self, context: WidgetContext) -> bool;
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext);
 
     fn states(&self) -> StateID;
 }
@@ -25,21 +27,36 @@ pub trait StateWidget {
 pub enum StateWrapper{
     Tree(u32),
     Local(Box<dyn StateWidget>),
+    Removed,
 }
 
 impl StateWrapper{
-    fn as_child<R>(&mut self, mut context: WidgetContext, operation: impl FnOnce(ChildUniq<StateRoot>, Env) -> R) -> R {
-        match replace(self, StateWrapper::Tree(0)) {
+    fn as_child<R>(&mut self, mut context: WidgetContext, operation: impl FnOnce(ChildUniq<StateRoot>, Env) -> R) -> Option<R> {
+        match replace(self, StateWrapper::Removed) {
             StateWrapper::Tree(index) => {
                 *self = StateWrapper::Tree(index);
                 let (a, b) = context.get_child(index);
-                operation(a, b)
+                Some(operation(a, b))
             }
             StateWrapper::Local(value) => {
                 let (a, b, c) = context.insert(StateRoot::new(value));
                 *self = StateWrapper::Tree(c);
-                operation(a, b)
+                Some(operation(a, b))
             }
+            StateWrapper::Removed => None,
+        }
+    }
+    /// Removes the wrapped StateRoot and its descendants from the widget-tree
+    /// This drops their Widgets and States, cancels their timers and tasks and unregisters them
+    /// from their States. Afterwards the StateWrapper behaves like an empty Widget.
+    /// The subtree receives Lifecycle::WidgetRemoved before it is removed.
+    pub fn remove(&mut self, mut context: WidgetContext) {
+        if let StateWrapper::Tree(index) = replace(self, StateWrapper::Removed) {
+            {
+                let (child, env) = context.get_child(index);
+                child.lifecycle(&Lifecycle::WidgetRemoved, env);
+            }
+            context.remove_child(index);
         }
     }
 }
@@ -47,23 +64,29 @@ impl StateWrapper{
 
 impl<T: Clone> Widget<T> for StateWrapper {
     fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
-        self.as_child(context, move|child, env|child.draw(painter, size, dirty_rect, env))
+        self.as_child(context, move|child, env|child.draw(painter, size, dirty_rect, env));
     }
 
     fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse {
-        self.as_child(context, move|child, env|child.handle_event(event, env))
+        self.as_child(context, move|child, env|{
+            if env.routes_to(child.index()) {
+                child.handle_event(event, env)
+            } else {
+                EventResponse::NONE
+            }
+        }).unwrap_or(EventResponse::NONE)
     }
 
     fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
-        self.as_child(context, move|child, env|child.get_pref_size(env))
+        self.as_child(context, move|child, env|child.get_pref_size(env)).unwrap_or(PrefSize::zero())
     }
 
     fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
-        self.as_child(context, move|child, env|child.layout(size, env))
+        self.as_child(context, move|child, env|child.layout(size, env));
     }
 
     fn build(&mut self, context: WidgetContext) {
-        self.as_child(context, move|child, env|child.build(env))
+        self.as_child(context, move|child, env|child.build(env));
     }
 
     fn update(&mut self, new: &T, old: Option<&T>) -> Change {
@@ -72,36 +95,53 @@ impl<T: Clone> Widget<T> for StateWrapper {
     }
 
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {
-        self.as_child(context, move|child, env|child.traverse_focus(env))
+        self.as_child(context, move|child, env|child.traverse_focus(env)).unwrap_or(false)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        match event {
+            //The wrapped StateRoot gets its own SizeChanged in its layout
+            Lifecycle::SizeChanged(_) => {}
+            Lifecycle::WidgetRemoved => self.remove(context),
+            _ => {
+                self.as_child(context, move|child, env|child.lifecycle(event, env));
+            }
+        }
     }
 }
 
-pub fn state<T: Clone + Send + Sync + 'static>(state: &CloneState<T>, widget: impl Widget<T> + 'static) -> StateWrapper {
+/// Binds the Widget to the State (for example a CloneState or a Derived)
+/// The Widget is updated whenever the State commits
+pub fn state<T, S, W>(state: &S, widget: W) -> StateWrapper
+    where T: Clone + 'static, S: State<T> + Clone + 'static, W: Widget<T> + 'static {
     StateWrapper::Local(
         Box::new(
             StateWidgetImpl {
                 state: state.clone(),
                 widget,
+                phantom: PhantomData,
             }
         )
     )
 }
 
-pub(crate) struct StateWidgetImpl<T: Clone, W: Widget<T>> {
-    state: CloneState<T>,
+pub(crate) struct StateWidgetImpl<T: Clone, S: State<T>, W: Widget<T>> {
+    state: S,
     widget: W,
+    phantom: PhantomData<T>,
 }
 
-impl<T: Clone + Send + Sync + 'static, W: Widget<T>> StateWidgetImpl<T, W> {
-    pub(crate) fn new(state: CloneState<T>, widget: W) -> Self {
+impl<T: Clone, S: State<T>, W: Widget<T>> StateWidgetImpl<T, S, W> {
+    pub(crate) fn new(state: S, widget: W) -> Self {
         StateWidgetImpl {
             state,
             widget,
+            phantom: PhantomData,
         }
     }
 }
 
-impl<T: Clone + Send + Sync + 'static, W: Widget<T>> StateWidget for StateWidgetImpl<T, W> {
+impl<T: Clone, S: State<T>, W: Widget<T>> StateWidget for StateWidgetImpl<T, S, W> {
     fn update(&mut self) -> Change {
         let widget = &mut self.widget;
         self.state.with_fetched_value(|new, old|widget.update(new, old))
@@ -135,6 +175,10 @@ impl<T: Clone + Send + Sync + 'static, W: Widget<T>> StateWidget for StateWidget
         self.widget.traverse_focus(context)
     }
 
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
+
     fn states(&self) -> StateID {
         self.state.get_id()
     }
@@ -154,4 +198,43 @@ impl<T: Clone + Send + Sync + 'static, W: Widget<T>> StateWidget for StateWidget
 struct StaticWidget<T: Clone, W: Widget<T>> {
     data: T,
     widget: W,
-}*/
\ No newline at end of file
+}*/
+
+/// Replaces the StateWidget of a removed StateRoot
+pub(crate) struct RemovedStateWidget(StateID);
+
+impl RemovedStateWidget {
+    pub(crate) fn new() -> Self {
+        RemovedStateWidget(StateID::new())
+    }
+}
+
+impl StateWidget for RemovedStateWidget {
+    fn update(&mut self) -> Change {
+        Change::None
+    }
+
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext) {}
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext) -> EventResponse {
+        EventResponse::NONE
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext) -> PrefSize {
+        PrefSize::zero()
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext) {}
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        false
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {}
+
+    fn states(&self) -> StateID {
+        self.0
+    }
+}
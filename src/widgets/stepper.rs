@@ -0,0 +1,47 @@
+use crate::widgets::{Widget, button};
+use crate::widgets::text::{Label, DynLabel};
+use crate::widgets::layout::{Container, HBox, Spacing};
+use crate::state::key::Key;
+use std::ops::{Add, Sub};
+use std::fmt::Display;
+
+/// A number, which can be changed by a stepper
+pub trait Steppable: Copy + PartialOrd + Display + Add<Output=Self> + Sub<Output=Self> + 'static {}
+
+impl<T: Copy + PartialOrd + Display + Add<Output=T> + Sub<Output=T> + 'static> Steppable for T {}
+
+fn change<T: Steppable>(mut data: Key<T>, value: T, min: T, max: T) {
+    let value = if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    };
+    if *data != value {
+        data.change(|current|*current = value);
+    }
+}
+
+/// Shows the value between a minus and a plus button, which change it by step within min and max
+pub fn stepper<T: Steppable>(step: T, min: T, max: T) -> impl Widget<T> {
+    Container::new(HBox::new(Spacing::Left, 2.0))
+        .child(button(Label::new("-", None), None, move|_, data: Key<T>|{
+            let value = *data;
+            //Comparing the distance to the bound avoids overflows at the limits of the type
+            if value > min && value - min >= step {
+                change(data, value - step, min, max)
+            } else {
+                change(data, min, min, max)
+            }
+        }))
+        .child(DynLabel::new(|value: &T|value.to_string()))
+        .child(button(Label::new("+", None), None, move|_, data: Key<T>|{
+            let value = *data;
+            if value < max && max - value >= step {
+                change(data, value + step, min, max)
+            } else {
+                change(data, max, min, max)
+            }
+        }))
+}
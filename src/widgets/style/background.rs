@@ -4,7 +4,7 @@ use druid_shell::kurbo::{Rect, Size};
 use crate::state::key::Key;
 use crate::widgets::style::Background;
 use crate::app::widget_graph::WidgetContext;
-use crate::app::event::{EventResponse, Event, Change};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
 use crate::app::size::PrefSize;
 
 pub struct BackgroundWrapper<W> {
@@ -54,4 +54,8 @@ impl<T: Clone, W: Widget<T>> Widget<T> for BackgroundWrapper<W> {
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {
         self.widget.traverse_focus(context)
     }
+
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {
+        self.widget.lifecycle(event, context)
+    }
 }
\ No newline at end of file
@@ -66,6 +66,174 @@ static STYLES: Lazy<(HashMap<String, Arc<StyleAtlas>>, Arc<StyleAtlas>)> = Lazy:
 
     map.insert("textfield".to_string(), Arc::new(style_atlas));
 
+    //Checkbox, radio and switch share their sheets, the active sheet is drawn inside the indicator
+    //while checked
+    for (name, corners) in [("checkbox", 3.0), ("radio", 8.0), ("switch", 8.0)].iter() {
+        style = Style{
+            margin: 0.0,
+            padding: 0.0,
+            border: 1.0,
+            border_style: StrokeStyle::new(),
+            border_fill: Fill::Solid(Color::grey8(120)),
+            background: Some(Background::from_color(Color::grey8(40))),
+            corners: *corners,
+        };
+
+        let mut style_atlas = StyleAtlas::new(style.clone());
+
+        style.background = Some(Background::from_color(Color::grey8(70)));
+
+        style_atlas.hovered = Some(style.clone());
+
+        style.border_fill = Fill::Solid(Color::rgb8(70, 80, 100));
+        style.border = 2.0;
+
+        style_atlas.focused = Some(style.clone());
+        style_atlas.hovered_focused = Some(style.clone());
+
+        style.border = 1.0;
+        style.background = Some(Background::from_color(Color::rgb8(60, 110, 200)));
+        style.border_fill = Fill::Solid(Color::rgb8(90, 140, 230));
+
+        style_atlas.active = Some(style.clone());
+
+        style.background = Some(Background::from_color(Color::grey8(100)));
+        style.border_fill = Fill::Solid(Color::grey8(200));
+
+        style_atlas.pressed = Some(style.clone());
+
+        map.insert(name.to_string(), Arc::new(style_atlas));
+    }
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 0.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(80)),
+        background: Some(Background::from_color(Color::grey8(90))),
+        corners: 2.0,
+    };
+
+    map.insert("slider_track".to_string(), Arc::new(StyleAtlas::new(style.clone())));
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 1.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(120)),
+        background: Some(Background::from_color(Color::grey8(180))),
+        corners: 7.0,
+    };
+
+    let mut style_atlas = StyleAtlas::new(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(220)));
+
+    style_atlas.hovered = Some(style.clone());
+
+    style.border = 2.0;
+    style.border_fill = Fill::Solid(Color::rgb8(70, 80, 100));
+
+    style_atlas.hovered_focused = Some(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(180)));
+
+    style_atlas.focused = Some(style.clone());
+
+    style.border = 1.0;
+    style.background = Some(Background::from_color(Color::rgb8(60, 110, 200)));
+    style.border_fill = Fill::Solid(Color::grey8(200));
+
+    style_atlas.pressed = Some(style.clone());
+
+    map.insert("slider_thumb".to_string(), Arc::new(style_atlas));
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 1.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(80)),
+        background: Some(Background::from_color(Color::grey8(40))),
+        corners: 4.0,
+    };
+
+    map.insert("progress_track".to_string(), Arc::new(StyleAtlas::new(style.clone())));
+
+    style.border = 0.0;
+    style.background = Some(Background::from_color(Color::rgb8(60, 110, 200)));
+
+    map.insert("progress_fill".to_string(), Arc::new(StyleAtlas::new(style.clone())));
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 0.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(100)),
+        background: Some(Background::from_color(Color::grey8(45))),
+        corners: 0.0,
+    };
+
+    let mut style_atlas = StyleAtlas::new(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(70)));
+
+    style_atlas.hovered = Some(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(90)));
+    style.border = 1.0;
+
+    style_atlas.active = Some(style.clone());
+
+    map.insert("tab".to_string(), Arc::new(style_atlas));
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 0.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(100)),
+        background: None,
+        corners: 0.0,
+    };
+
+    let mut style_atlas = StyleAtlas::new(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(55)));
+
+    style_atlas.hovered = Some(style.clone());
+
+    style.background = Some(Background::from_color(Color::rgb8(50, 80, 140)));
+
+    style_atlas.active = Some(style.clone());
+
+    map.insert("list_row".to_string(), Arc::new(style_atlas));
+
+    style = Style{
+        margin: 0.0,
+        padding: 0.0,
+        border: 0.0,
+        border_style: StrokeStyle::new(),
+        border_fill: Fill::Solid(Color::grey8(100)),
+        background: Some(Background::from_color(Color::grey8(45))),
+        corners: 0.0,
+    };
+
+    let mut style_atlas = StyleAtlas::new(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(60)));
+
+    style_atlas.hovered = Some(style.clone());
+
+    style.background = Some(Background::from_color(Color::grey8(75)));
+
+    style_atlas.pressed = Some(style.clone());
+
+    map.insert("table_header".to_string(), Arc::new(style_atlas));
+
     (map, default)
 });
 
@@ -134,6 +134,36 @@ impl DynamicStyle {
             state: State::new()
         }
     }
+    /// Selects the active sheet while active is set, returns true if the sheet changed
+    pub fn set_active(&mut self, active: bool) -> bool {
+        let mut new_state = self.state;
+        new_state.active = active;
+        self.set_state(new_state)
+    }
+    /// Selects the focused sheets while focused is set, returns true if the sheet changed
+    pub fn set_focused(&mut self, focused: bool) -> bool {
+        let mut new_state = self.state;
+        new_state.focused = focused;
+        self.set_state(new_state)
+    }
+    pub fn mouse_state(&self) -> MouseState {
+        self.state.mouse_state
+    }
+
+    fn set_state(&mut self, new_state: State) -> bool {
+        if new_state == self.state {
+            return false;
+        }
+        let old_sheet = self.atlas.as_owner().sheet(self.state);
+        let new_sheet = self.atlas.as_owner().sheet(new_state);
+        self.state = new_state;
+        if new_sheet != old_sheet {
+            update(&mut self.atlas ,|this|this.into_owner().get(new_sheet));
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl Wrapper for DynamicStyle {
@@ -162,15 +192,11 @@ impl Wrapper for DynamicStyle {
             }
             _ => {}
         }
-        if new_state != self.state {
-            let new_sheet = self.atlas.as_owner().sheet(new_state);
-            if new_sheet != self.atlas.as_owner().sheet(self.state) {
-                self.state = new_state;
-                update(&mut self.atlas ,|this|this.into_owner().get(new_sheet));
-                return WrapperResponse::Changed;
-            }
+        if self.set_state(new_state) {
+            WrapperResponse::Changed
+        } else {
+            WrapperResponse::Unchanged
         }
-        WrapperResponse::Unchanged
     }
 
     fn get_left_top_padding(&self) -> Size {
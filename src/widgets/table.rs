@@ -0,0 +1,863 @@
+use crate::widgets::Widget;
+use crate::widgets::scrollbar::{self, ThumbDrag};
+use crate::widgets::capture::captured_event;
+use crate::widgets::text::{Label, DynLabel};
+use crate::widgets::style::{StyleAtlas, get_style};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Point, BezPath, Line};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::{KeyCode, Cursor, MouseEvent};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+const HEADER_HEIGHT: f64 = 24.0;
+/// The distance to the edge of a header, at which a column can be resized
+const GRIP: f64 = 4.0;
+
+/// The column and direction a Table is sorted by
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sort {
+    /// the index of the column in the order it was added to the Table
+    pub column: usize,
+    pub ascending: bool,
+}
+
+/// The rows of a Table with the selection and the sort order
+///
+/// The selection contains the indices of the rows, sorting doesn't move the rows, only their order
+/// on screen. Use insert and remove to keep the selection on the same rows, changing rows directly
+/// leaves the selected indices as they are.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableData<R> {
+    pub rows: Vec<R>,
+    pub selection: BTreeSet<usize>,
+    pub sort: Option<Sort>,
+}
+
+impl<R> TableData<R> {
+    pub fn new(rows: Vec<R>) -> Self {
+        TableData {
+            rows,
+            selection: BTreeSet::new(),
+            sort: None,
+        }
+    }
+    /// Inserts the row, the selected rows behind it stay selected
+    pub fn insert(&mut self, index: usize, row: R) {
+        self.rows.insert(index, row);
+        self.selection = self.selection.iter()
+            .map(|selected|if *selected >= index {selected + 1} else {*selected})
+            .collect();
+    }
+    /// Removes the row and its selection, the selected rows behind it stay selected
+    pub fn remove(&mut self, index: usize) -> R {
+        self.selection = self.selection.iter()
+            .filter(|selected|**selected != index)
+            .map(|selected|if *selected > index {selected - 1} else {*selected})
+            .collect();
+        self.rows.remove(index)
+    }
+    pub fn selected_rows(&self) -> impl Iterator<Item=&R> {
+        let rows = &self.rows;
+        self.selection.iter().filter_map(move|index|rows.get(*index))
+    }
+}
+
+/// How the width of a column is chosen
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ColumnWidth {
+    Fixed(f64),
+    /// Shares the space left by the fixed columns with the other flexible ones by the weight
+    Flex(f64),
+}
+
+/// A column of a Table
+pub struct Column<R> {
+    title: Label,
+    factory: Box<dyn Fn() -> Box<dyn Widget<R>>>,
+    compare: Option<Box<dyn Fn(&R, &R) -> Ordering>>,
+    width: ColumnWidth,
+    min_width: f64,
+}
+
+impl<R: Clone + 'static> Column<R> {
+    /// A column showing the text of each row, it is sorted by the text
+    pub fn text(title: impl Into<String>, text: impl Fn(&R) -> String + 'static) -> Self {
+        let text = Rc::new(text);
+        let cell_text = text.clone();
+        let mut column = Column::widget(title, move||{
+            let text = cell_text.clone();
+            DynLabel::new(move|row: &R|text(row))
+        });
+        column.compare = Some(Box::new(move|a, b|text(a).cmp(&text(b))));
+        column
+    }
+    /// A column with a Widget created by the factory in every visible cell, it isn't sortable
+    /// unless sort_by is set
+    pub fn widget<W: Widget<R> + 'static>(title: impl Into<String>, factory: impl Fn() -> W + 'static) -> Self {
+        Column {
+            title: Label::new(title, None),
+            factory: Box::new(move||Box::new(factory())),
+            compare: None,
+            width: ColumnWidth::Flex(1.0),
+            min_width: 30.0,
+        }
+    }
+    pub fn width(mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+    pub fn min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self
+    }
+    pub fn sort_by(mut self, compare: impl Fn(&R, &R) -> Ordering + 'static) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+}
+
+struct TableRow<R> {
+    /// the position on screen
+    position: usize,
+    /// the index in TableData::rows
+    index: usize,
+    /// the cells by column
+    cells: Vec<Box<dyn Widget<R>>>,
+    widths: Vec<f64>,
+}
+
+#[derive(Copy, Clone)]
+enum HeaderDrag {
+    None,
+    Resize { column: usize, start_x: f64, start_width: f64 },
+    Press { column: usize, start_x: f64 },
+    Move { column: usize },
+}
+
+/// A table of rows with a header of resizable, reorderable and sortable columns
+///
+/// Only the visible rows have cell Widgets, they are recycled while scrolling like in a
+/// VirtualList. Clicking a header sorts by the column, dragging its edge resizes it and dragging
+/// the header moves it. With multi_select Ctrl+click toggles a row and Shift+click or Shift+arrows
+/// select a range.
+pub struct Table<R> {
+    columns: Vec<Column<R>>,
+    /// the columns in the order on screen
+    order: Vec<usize>,
+    widths: Vec<f64>,
+    row_height: f64,
+    multi_select: bool,
+    /// the row index for each position on screen
+    view: Vec<usize>,
+    rows: Vec<TableRow<R>>,
+    spare: Vec<Vec<Box<dyn Widget<R>>>>,
+    row_style: Arc<StyleAtlas>,
+    header_style: Arc<StyleAtlas>,
+    offset: f64,
+    thumb: ThumbDrag,
+    drag: HeaderDrag,
+    header_hovered: Option<usize>,
+    resize_cursor: bool,
+    /// the hovered cell as position and column
+    hovered: Option<(usize, usize)>,
+    /// the cell which consumed the last MouseDown
+    captured: Option<(usize, usize)>,
+    cursor: Option<usize>,
+    anchor: Option<usize>,
+    focused: bool,
+    size: Size,
+}
+
+impl<R: Clone + 'static> Table<R> {
+    pub fn new() -> Self {
+        Table {
+            columns: Vec::new(),
+            order: Vec::new(),
+            widths: Vec::new(),
+            row_height: 24.0,
+            multi_select: false,
+            view: Vec::new(),
+            rows: Vec::new(),
+            spare: Vec::new(),
+            row_style: get_style("list_row"),
+            header_style: get_style("table_header"),
+            offset: 0.0,
+            thumb: ThumbDrag::new(),
+            drag: HeaderDrag::None,
+            header_hovered: None,
+            resize_cursor: false,
+            hovered: None,
+            captured: None,
+            cursor: None,
+            anchor: None,
+            focused: false,
+            size: Size::ZERO,
+        }
+    }
+    pub fn column(mut self, column: Column<R>) -> Self {
+        self.order.push(self.columns.len());
+        self.widths.push(0.0);
+        self.columns.push(column);
+        self
+    }
+    pub fn row_height(mut self, height: f64) -> Self {
+        self.row_height = height.max(1.0);
+        self
+    }
+    /// Allows selecting more than one row
+    pub fn multi_select(mut self) -> Self {
+        self.multi_select = true;
+        self
+    }
+    pub fn with_styles(mut self, header: Arc<StyleAtlas>, row: Arc<StyleAtlas>) -> Self {
+        self.header_style = header;
+        self.row_style = row;
+        self
+    }
+
+    fn body_height(&self) -> f64 {
+        (self.size.height - HEADER_HEIGHT).max(0.0)
+    }
+
+    /// Distributes the width between the columns
+    fn compute_widths(&mut self) {
+        let fixed: f64 = self.columns.iter()
+            .map(|column|match column.width {
+                ColumnWidth::Fixed(width) => width.max(column.min_width),
+                ColumnWidth::Flex(_) => 0.0,
+            })
+            .sum();
+        let weights: f64 = self.columns.iter()
+            .map(|column|match column.width {
+                ColumnWidth::Flex(weight) => weight,
+                ColumnWidth::Fixed(_) => 0.0,
+            })
+            .sum();
+        let space = (self.size.width - fixed).max(0.0);
+        for (column, width) in self.columns.iter().zip(self.widths.iter_mut()) {
+            *width = match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Flex(weight) if weights > 0.0 => space * weight / weights,
+                ColumnWidth::Flex(_) => 0.0,
+            }.max(column.min_width);
+        }
+    }
+
+    /// Returns the left edge of every column by index
+    fn column_x(&self) -> Vec<f64> {
+        let mut xs = vec![0.0; self.columns.len()];
+        let mut x = 0.0;
+        for &column in self.order.iter() {
+            xs[column] = x;
+            x += self.widths[column];
+        }
+        xs
+    }
+
+    fn column_at(&self, x: f64) -> Option<usize> {
+        let xs = self.column_x();
+        self.order.iter().copied().find(|column|x >= xs[*column] && x < xs[*column] + self.widths[*column])
+    }
+
+    /// Returns the column whose right edge is at x
+    fn edge_at(&self, x: f64) -> Option<usize> {
+        let xs = self.column_x();
+        self.order.iter().copied().find(|column|(xs[*column] + self.widths[*column] - x).abs() <= GRIP)
+    }
+
+    fn position_at(&self, y: f64) -> Option<usize> {
+        let position = ((y - HEADER_HEIGHT + self.offset) / self.row_height).floor();
+        if y < HEADER_HEIGHT || position < 0.0 || position as usize >= self.view.len() {
+            None
+        } else {
+            Some(position as usize)
+        }
+    }
+
+    fn row_y(&self, position: usize) -> f64 {
+        HEADER_HEIGHT + position as f64 * self.row_height - self.offset
+    }
+
+    fn sort_view(&mut self, data: &TableData<R>) {
+        let mut view: Vec<usize> = (0..data.rows.len()).collect();
+        if let Some(sort) = data.sort {
+            if let Some(compare) = self.columns.get(sort.column).and_then(|column|column.compare.as_ref()) {
+                view.sort_by(|a, b|{
+                    let order = compare(&data.rows[*a], &data.rows[*b]);
+                    if sort.ascending {order} else {order.reverse()}
+                });
+            }
+        }
+        self.view = view;
+    }
+
+    fn clamp_offset(&mut self) {
+        let max = (self.view.len() as f64 * self.row_height - self.body_height()).max(0.0);
+        self.offset = self.offset.min(max).max(0.0);
+    }
+
+    fn scroll_to(&mut self, position: usize) {
+        let top = position as f64 * self.row_height;
+        let bottom = top + self.row_height;
+        if top < self.offset {
+            self.offset = top;
+        } else if bottom > self.offset + self.body_height() {
+            self.offset = bottom - self.body_height();
+        }
+    }
+
+    /// Sends the mouse event to the cell which captured the mouse
+    fn captured_event(&mut self, (position, column): (usize, usize), event: Event, context: WidgetContext, mut data: Key<TableData<R>>) -> EventResponse {
+        if let Event::MouseUp(_) = event {
+            self.captured = None;
+        }
+        let shift = Vec2::new(self.column_x()[column], self.row_y(position));
+        let cell_size = Size::new(self.widths[column], self.row_height);
+        let row = match self.rows.iter_mut().find(|row|row.position == position) {
+            Some(row) => row,
+            None => return EventResponse::NONE,
+        };
+        let (response, exited) = captured_event(&mut *row.cells[column], shift, cell_size, event, context, Self::row_key(&mut data, row.index));
+        if exited {
+            self.hovered = None;
+            return response.merge(EventResponse::changed(self.size, false));
+        }
+        response
+    }
+
+    fn row_key<'b>(data: &'b mut Key<TableData<R>>, index: usize) -> Key<'b, R> {
+        Key::new(&mut data.value.rows[index], &mut *data.changed_flag)
+    }
+
+    /// Returns the cells for a row, recycled if possible
+    fn instantiate(&mut self, position: usize, index: usize, mut context: WidgetContext, item: &R) -> TableRow<R> {
+        let mut cells = match self.spare.pop() {
+            Some(mut cells) => {
+                for cell in cells.iter_mut() {
+                    cell.lifecycle(&Lifecycle::Shown, context.id());
+                }
+                cells
+            }
+            None => {
+                let mut cells = Vec::new();
+                for column in self.columns.iter() {
+                    let mut cell = (column.factory)();
+                    cell.build(context.id());
+                    cell.lifecycle(&Lifecycle::WidgetAdded, context.id());
+                    cells.push(cell);
+                }
+                cells
+            }
+        };
+        for cell in cells.iter_mut() {
+            let _ = cell.update(item, None);
+        }
+        TableRow {
+            position,
+            index,
+            widths: vec![-1.0; cells.len()],
+            cells,
+        }
+    }
+
+    fn hide(&mut self, mut row: TableRow<R>, mut context: WidgetContext) {
+        for cell in row.cells.iter_mut() {
+            cell.lifecycle(&Lifecycle::Hidden, context.id());
+        }
+        self.spare.push(row.cells);
+    }
+
+    /// Creates the cells of the visible rows and lays them out
+    fn arrange(&mut self, mut context: WidgetContext, data: &TableData<R>) {
+        if self.view.len() != data.rows.len() {
+            self.sort_view(data);
+        }
+        self.compute_widths();
+        self.clamp_offset();
+
+        let first = (self.offset / self.row_height).floor() as usize;
+        let last = (((self.offset + self.body_height()) / self.row_height).ceil() as usize).min(self.view.len());
+
+        let mut old = Vec::new();
+        for row in std::mem::replace(&mut self.rows, Vec::new()) {
+            if row.position < first || row.position >= last {
+                self.hide(row, context.id());
+            } else {
+                old.push(row);
+            }
+        }
+
+        for position in first..last {
+            let index = self.view[position];
+            let item = &data.rows[index];
+            let mut row = match old.iter().position(|row|row.position == position) {
+                Some(existing) => old.swap_remove(existing),
+                None => self.instantiate(position, index, context.id(), item),
+            };
+            if row.index != index {
+                row.index = index;
+                for cell in row.cells.iter_mut() {
+                    let _ = cell.update(item, None);
+                }
+            }
+            for ((cell, width), new_width) in row.cells.iter_mut().zip(row.widths.iter_mut()).zip(self.widths.iter()) {
+                let size = Size::new(*new_width, self.row_height);
+                if *width != *new_width {
+                    *width = *new_width;
+                    cell.lifecycle(&Lifecycle::SizeChanged(size), context.id());
+                }
+                cell.layout(size, context.id(), item);
+            }
+            self.rows.push(row);
+        }
+        for row in old {
+            self.hide(row, context.id());
+        }
+
+        if self.hovered.map_or(false, |(position, _)|position < first || position >= last) {
+            self.hovered = None;
+        }
+    }
+
+    fn set_resize_cursor(&mut self, resize: bool, context: &mut WidgetContext) {
+        if resize != self.resize_cursor {
+            self.resize_cursor = resize;
+            context.set_cursor(if resize {Cursor::ResizeLeftRight} else {Cursor::Arrow});
+        }
+    }
+
+    /// Moves the mouse focus to the cell, sending MouseExit and MouseEnter
+    fn hover(&mut self, cell: Option<(usize, usize)>, mouse: Option<&MouseEvent>, context: &mut WidgetContext, data: &mut Key<TableData<R>>) -> EventResponse {
+        if cell == self.hovered {
+            return EventResponse::NONE;
+        }
+        let xs = self.column_x();
+        let mut response = EventResponse::changed(self.size, false);
+        if let Some((position, column)) = self.hovered.take() {
+            let shift = Vec2::new(xs[column], self.row_y(position));
+            if let Some(row) = self.rows.iter_mut().find(|row|row.position == position) {
+                let cell_response = row.cells[column].handle_event(Event::MouseExit, context.id(), Self::row_key(data, row.index));
+                response = response.merge(cell_response.shift(shift));
+            }
+        }
+        if let (Some((position, column)), Some(mouse)) = (cell, mouse) {
+            let shift = Vec2::new(xs[column], self.row_y(position));
+            if let Some(row) = self.rows.iter_mut().find(|row|row.position == position) {
+                let mut mouse = mouse.clone();
+                mouse.pos -= shift;
+                let cell_response = row.cells[column].handle_event(Event::MouseEnter(mouse), context.id(), Self::row_key(data, row.index));
+                response = response.merge(cell_response.shift(shift));
+            }
+        }
+        self.hovered = cell;
+        response
+    }
+
+    fn hover_header(&mut self, column: Option<usize>) -> EventResponse {
+        if column == self.header_hovered {
+            return EventResponse::NONE;
+        }
+        self.header_hovered = column;
+        EventResponse::changed(Size::new(self.size.width, HEADER_HEIGHT), false)
+    }
+
+    /// Continues a resize or move of a column, returns None if there is none
+    fn drag_header(&mut self, event: &Event, mouse: &MouseEvent, context: &mut WidgetContext, data: &mut Key<TableData<R>>) -> Option<EventResponse> {
+        match (self.drag, event) {
+            (HeaderDrag::None, _) => None,
+            (drag, Event::MouseUp(_)) => {
+                if let HeaderDrag::Press {column, ..} = drag {
+                    if self.columns[column].compare.is_some() {
+                        let ascending = match data.sort {
+                            Some(sort) if sort.column == column => !sort.ascending,
+                            _ => true,
+                        };
+                        data.change(|table|table.sort = Some(Sort {column, ascending}));
+                    }
+                }
+                self.drag = HeaderDrag::None;
+                Some(EventResponse::changed(self.size, true))
+            }
+            (HeaderDrag::Resize {column, start_x, start_width}, Event::MouseMove(_)) => {
+                let width = (start_width + mouse.pos.x - start_x).max(self.columns[column].min_width);
+                self.columns[column].width = ColumnWidth::Fixed(width);
+                self.arrange(context.id(), &**data);
+                Some(EventResponse::changed(self.size, true))
+            }
+            (HeaderDrag::Press {column, start_x}, Event::MouseMove(_)) => {
+                if (mouse.pos.x - start_x).abs() > GRIP {
+                    self.drag = HeaderDrag::Move {column};
+                }
+                Some(EventResponse::DELETED)
+            }
+            (HeaderDrag::Move {column}, Event::MouseMove(_)) => {
+                let target = self.column_at(mouse.pos.x.max(0.0))
+                    .or_else(||self.order.last().copied())
+                    .and_then(|target|self.order.iter().position(|other|*other == target));
+                let current = self.order.iter().position(|other|*other == column);
+                if let (Some(target), Some(current)) = (target, current) {
+                    if target != current {
+                        self.order.remove(current);
+                        self.order.insert(target, column);
+                        self.hovered = None;
+                    }
+                }
+                Some(EventResponse::changed(self.size, true))
+            }
+            _ => Some(EventResponse::DELETED),
+        }
+    }
+
+    fn header_event(&mut self, event: &Event, mouse: &MouseEvent, context: &mut WidgetContext) -> EventResponse {
+        let edge = self.edge_at(mouse.pos.x);
+        self.set_resize_cursor(edge.is_some(), context);
+        let column = self.column_at(mouse.pos.x);
+        let response = self.hover_header(column);
+        if let Event::MouseDown(_) = event {
+            self.drag = match (edge, column) {
+                (Some(column), _) => HeaderDrag::Resize {
+                    column,
+                    start_x: mouse.pos.x,
+                    start_width: self.widths[column],
+                },
+                (None, Some(column)) => HeaderDrag::Press {
+                    column,
+                    start_x: mouse.pos.x,
+                },
+                (None, None) => HeaderDrag::None,
+            };
+            return response.merge(EventResponse::changed(Size::new(self.size.width, HEADER_HEIGHT), true));
+        }
+        response
+    }
+
+    /// Selects the row at the position like a click with the modifiers
+    fn select(&mut self, mut context: WidgetContext, data: &mut Key<TableData<R>>, position: usize, extend: bool, toggle: bool) -> EventResponse {
+        if self.view.is_empty() {
+            return EventResponse::DELETED;
+        }
+        let position = position.min(self.view.len() - 1);
+        let index = self.view[position];
+        let selection = if self.multi_select && extend {
+            let anchor = self.anchor.unwrap_or(position).min(self.view.len() - 1);
+            let (from, to) = if anchor < position {(anchor, position)} else {(position, anchor)};
+            self.view[from..=to].iter().copied().collect()
+        } else if self.multi_select && toggle {
+            self.anchor = Some(position);
+            let mut selection = data.selection.clone();
+            if !selection.remove(&index) {
+                selection.insert(index);
+            }
+            selection
+        } else {
+            self.anchor = Some(position);
+            let mut selection = BTreeSet::new();
+            selection.insert(index);
+            selection
+        };
+        if data.selection != selection {
+            data.change(|table|table.selection = selection);
+        }
+        self.cursor = Some(position);
+        self.scroll_to(position);
+        self.arrange(context.id(), &**data);
+        EventResponse::changed(self.size, true)
+    }
+
+    fn page_rows(&self) -> usize {
+        ((self.body_height() / self.row_height) as usize).max(1)
+    }
+
+    fn draw_header(&mut self, painter: &mut Piet, mut context: WidgetContext, sort: Option<Sort>) {
+        let xs = self.column_x();
+        let moving = match self.drag {
+            HeaderDrag::Move {column} => Some(column),
+            _ => None,
+        };
+        let pressed = match self.drag {
+            HeaderDrag::Press {column, ..} => Some(column),
+            _ => None,
+        };
+        for &column in self.order.iter() {
+            let style = if pressed == Some(column) || moving == Some(column) {
+                self.header_style.pressed.as_ref()
+            } else if self.header_hovered == Some(column) {
+                self.header_style.hovered.as_ref()
+            } else {
+                None
+            }.unwrap_or(&self.header_style.idle);
+            let size = Size::new(self.widths[column], HEADER_HEIGHT);
+            let title = &mut self.columns[column].title;
+            let _ = painter.with_save(|painter|{
+                painter.transform(Affine::translate(Vec2::new(xs[column], 0.0)));
+                painter.clip(size.to_rect());
+                style.draw(painter, size);
+                let _ = painter.with_save(|painter|{
+                    painter.transform(Affine::translate(Vec2::new(0.0, (HEADER_HEIGHT - 17.0) / 2.0)));
+                    title.draw(painter, size, size.to_rect(), context.id(), &());
+                    Ok(())
+                });
+
+                if let Some(sort) = sort.filter(|sort|sort.column == column) {
+                    let center = Point::new(size.width - 10.0, HEADER_HEIGHT / 2.0);
+                    let direction = if sort.ascending {-1.0} else {1.0};
+                    let mut arrow = BezPath::new();
+                    arrow.move_to(center + Vec2::new(-4.0, -2.0 * direction));
+                    arrow.line_to(center + Vec2::new(4.0, -2.0 * direction));
+                    arrow.line_to(center + Vec2::new(0.0, 3.0 * direction));
+                    arrow.close_path();
+                    let brush = painter.solid_brush(Color::grey8(200));
+                    painter.fill(arrow, &brush);
+                }
+
+                let brush = painter.solid_brush(Color::grey8(90));
+                painter.stroke(Line::new((size.width - 0.5, 0.0), (size.width - 0.5, HEADER_HEIGHT)), &brush, 1.0);
+                Ok(())
+            });
+        }
+    }
+}
+
+impl<R: Clone + 'static> Widget<TableData<R>> for Table<R> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &TableData<R>) {
+        let xs = self.column_x();
+        let body = Rect::new(0.0, HEADER_HEIGHT, size.width, size.height);
+        for row in self.rows.iter_mut() {
+            let y = HEADER_HEIGHT + row.position as f64 * self.row_height - self.offset;
+            let row_rect = Rect::new(0.0, y, size.width, y + self.row_height);
+            if row_rect.intersect(dirty_rect).area() <= 0.0 {
+                continue;
+            }
+            let style = if data.selection.contains(&row.index) {
+                self.row_style.active.as_ref()
+            } else if self.hovered.map(|(position, _)|position) == Some(row.position) {
+                self.row_style.hovered.as_ref()
+            } else {
+                None
+            }.unwrap_or(&self.row_style.idle);
+            let item = &data.rows[row.index];
+            let row_height = self.row_height;
+            let order = &self.order;
+            let widths = &self.widths;
+            let _ = painter.with_save(|painter|{
+                painter.clip(body);
+                painter.transform(Affine::translate(Vec2::new(0.0, y)));
+                style.draw(painter, row_rect.size());
+                for &column in order.iter() {
+                    let cell_size = Size::new(widths[column], row_height);
+                    let shift = Vec2::new(xs[column], 0.0);
+                    let cell_dirty = (dirty_rect - Vec2::new(0.0, y) - shift).intersect(cell_size.to_rect());
+                    if cell_dirty.area() <= 0.0 {
+                        continue;
+                    }
+                    let cell = &mut row.cells[column];
+                    let _ = painter.with_save(|painter|{
+                        painter.transform(Affine::translate(shift));
+                        painter.clip(cell_size.to_rect());
+                        cell.draw(painter, cell_size, cell_dirty, context.id(), item);
+                        Ok(())
+                    });
+                }
+                Ok(())
+            });
+        }
+
+        scrollbar::draw(painter, body, self.view.len() as f64 * self.row_height, self.offset);
+
+        if dirty_rect.y0 < HEADER_HEIGHT {
+            self.draw_header(painter, context, data.sort);
+        }
+    }
+
+    fn handle_event(&mut self, mut event: Event, mut context: WidgetContext, mut data: Key<TableData<R>>) -> EventResponse {
+        if let Some(mouse) = event.mouse_event().cloned() {
+            if let Some(response) = self.drag_header(&event, &mouse, &mut context, &mut data) {
+                return response;
+            }
+            let body = Rect::new(0.0, HEADER_HEIGHT, self.size.width, HEADER_HEIGHT + self.body_height());
+            let total = self.view.len() as f64 * self.row_height;
+            if let Some(offset) = self.thumb.handle_event(&event, body, total, self.offset) {
+                self.offset = offset;
+                self.arrange(context.id(), &*data);
+                return EventResponse::changed(self.size, true);
+            }
+            if let Event::MouseDown(_) = event {
+                self.captured = None;
+            }
+            if context.is_pointer_captured() {
+                if let Some(cell) = self.captured {
+                    return self.captured_event(cell, event, context, data);
+                }
+            }
+            if mouse.pos.y < HEADER_HEIGHT {
+                let response = self.hover(None, None, &mut context, &mut data);
+                return response.merge(self.header_event(&event, &mouse, &mut context));
+            }
+
+            self.set_resize_cursor(false, &mut context);
+            let cell = self.position_at(mouse.pos.y).zip(self.column_at(mouse.pos.x));
+            let mut response = self.hover_header(None).merge(self.hover(cell, Some(&mouse), &mut context, &mut data));
+            if let Some((position, column)) = cell {
+                if let Event::MouseEnter(_) = event {
+                    //Already entered
+                } else {
+                    let shift = Vec2::new(self.column_x()[column], self.row_y(position));
+                    let row = match self.rows.iter_mut().find(|row|row.position == position) {
+                        Some(row) => row,
+                        None => return response,
+                    };
+                    event.shift(shift, Size::new(self.widths[column], self.row_height));
+                    let cell_response = row.cells[column].handle_event(event.clone(), context.id(), Self::row_key(&mut data, row.index));
+                    response = response.merge(cell_response.shift(shift));
+                    if !cell_response.is_present() {
+                        if let Event::MouseDown(_) = event {
+                            self.captured = Some((position, column));
+                        }
+                        return response;
+                    }
+                    if let Event::MouseDown(_) = event {
+                        let extend = mouse.mods.shift;
+                        let toggle = mouse.mods.ctrl || mouse.mods.meta;
+                        response = response.merge(self.select(context.id(), &mut data, position, extend, toggle));
+                    }
+                }
+            }
+            if let Event::Wheel(_) = event {
+                let offset = self.offset;
+                self.offset += mouse.wheel_delta.y;
+                self.arrange(context.id(), &*data);
+                if (self.offset - offset).abs() > std::f64::EPSILON {
+                    let cell = self.position_at(mouse.pos.y).zip(self.column_at(mouse.pos.x));
+                    let hover = self.hover(cell, Some(&mouse), &mut context, &mut data);
+                    response = response.merge(hover).merge(EventResponse::changed(self.size, true));
+                }
+            }
+            return response;
+        }
+
+        match event {
+            Event::MouseExit => {
+                //A header drag released outside of the Table is cancelled
+                self.drag = HeaderDrag::None;
+                self.set_resize_cursor(false, &mut context);
+                let response = self.hover_header(None);
+                response.merge(self.hover(None, None, &mut context, &mut data))
+            }
+            Event::KeyDown(key_event) if self.focused => {
+                let last = self.view.len().saturating_sub(1);
+                let cursor = self.cursor;
+                let position = match key_event.key_code {
+                    KeyCode::ArrowDown => cursor.map_or(0, |position|position + 1),
+                    KeyCode::ArrowUp => cursor.map_or(0, |position|position.saturating_sub(1)),
+                    KeyCode::PageDown => cursor.map_or(0, |position|position + self.page_rows()),
+                    KeyCode::PageUp => cursor.map_or(0, |position|position.saturating_sub(self.page_rows())),
+                    KeyCode::Home => 0,
+                    KeyCode::End => last,
+                    KeyCode::KeyA if key_event.mods.ctrl && self.multi_select => {
+                        let selection: BTreeSet<usize> = (0..data.rows.len()).collect();
+                        data.change(|table|table.selection = selection);
+                        return EventResponse::changed(self.size, true);
+                    }
+                    _ => return EventResponse::NONE,
+                };
+                self.select(context, &mut data, position, key_event.mods.shift, false)
+            }
+            event if event.is_broadcast() => {
+                let mut response = EventResponse::NONE;
+                for row in self.rows.iter_mut() {
+                    let y = HEADER_HEIGHT + row.position as f64 * self.row_height - self.offset;
+                    for cell in row.cells.iter_mut() {
+                        let cell_response = cell.handle_event(event.clone(), context.id(), Self::row_key(&mut data, row.index));
+                        response = response.merge(cell_response.shift(Vec2::new(0.0, y)));
+                    }
+                }
+                response
+            }
+            _ => EventResponse::NONE,
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &TableData<R>) -> PrefSize {
+        let min_width: f64 = self.columns.iter().map(|column|column.min_width).sum();
+        let mut pref = PrefSize::flexible(Size::new(min_width.max(200.0), HEADER_HEIGHT + self.row_height * 5.0));
+        pref.min_min_size(Size::new(min_width, HEADER_HEIGHT + self.row_height));
+        pref
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &TableData<R>) {
+        self.size = size;
+        self.arrange(context, data);
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &TableData<R>, old: Option<&TableData<R>>) -> Change {
+        let old_view = std::mem::replace(&mut self.view, Vec::new());
+        self.sort_view(new);
+
+        let mut change = Change::None;
+        for row in self.rows.iter_mut() {
+            let index = match self.view.get(row.position) {
+                Some(index) => *index,
+                None => continue,
+            };
+            let old_item = old.filter(|_|index == row.index).and_then(|old|old.rows.get(index));
+            row.index = index;
+            let mut changed = false;
+            for cell in row.cells.iter_mut() {
+                changed |= cell.update(&new.rows[index], old_item).changed();
+            }
+            if changed {
+                //Cells are laid out by the Table, their bounds can't change
+                let y = HEADER_HEIGHT + row.position as f64 * self.row_height - self.offset;
+                change = change.merge(Change::Content(Rect::new(0.0, y, self.size.width, y + self.row_height)));
+            }
+        }
+
+        //The cursor and the anchor are positions on screen, which point to other rows now
+        if old_view != self.view {
+            self.cursor = None;
+            self.anchor = None;
+        }
+        //States pass None as old value, the length of the last view tells if rows were added
+        if old_view.len() != self.view.len() {
+            return Change::Bounds;
+        }
+        let unchanged = old.map_or(false, |old|old.selection == new.selection && old.sort == new.sort);
+        if old_view != self.view || !unchanged {
+            change = change.merge(Change::Content(self.size.to_rect()));
+        }
+        change
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.focused = !self.focused;
+        self.focused
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        //Spare cells are hidden already, they are only told about their removal
+        if event.is_broadcast() {
+            for row in self.rows.iter_mut() {
+                for cell in row.cells.iter_mut() {
+                    cell.lifecycle(event, context.id());
+                }
+            }
+            if *event == Lifecycle::WidgetRemoved {
+                for cells in self.spare.iter_mut() {
+                    for cell in cells.iter_mut() {
+                        cell.lifecycle(event, context.id());
+                    }
+                }
+            }
+        }
+    }
+}
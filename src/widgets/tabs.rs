@@ -0,0 +1,372 @@
+use crate::widgets::Widget;
+use crate::widgets::capture::captured_event;
+use crate::widgets::text::Label;
+use crate::widgets::style::{StyleAtlas, DynamicStyle, Fill, get_style};
+use crate::widgets::raw::Wrapper;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2};
+use druid_shell::piet::{Piet, RenderContext};
+use druid_shell::KeyCode;
+use std::sync::Arc;
+
+const HEADER_HEIGHT: f64 = 24.0;
+
+struct Tab {
+    label: Label,
+    style: DynamicStyle,
+    page: Box<dyn Widget<usize>>,
+    /// the horizontal position and width of the header button
+    header: (f64, f64),
+}
+
+/// A strip of tab buttons above the page of the selected tab
+///
+/// The data is the index of the selected tab. Only the selected page is drawn, laid out and
+/// receives events, the other pages keep their state and get Lifecycle::Hidden. Pages usually
+/// are StateWrappers of their own state. Ctrl+Tab selects the next tab, Ctrl+Shift+Tab the
+/// previous one.
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    style: Arc<StyleAtlas>,
+    selected: usize,
+    /// the last selected tab, whose page still has to be hidden
+    switched_from: Option<usize>,
+    hovered: Option<usize>,
+    page_hovered: bool,
+    /// the page consumed the last MouseDown
+    page_captured: bool,
+    /// The header has the focus, if the page has no focusable Widget
+    header_focused: bool,
+    /// The focus is inside of the selected page
+    page_focused: bool,
+    page_size: Size,
+    size: Size,
+}
+
+impl Tabs {
+    pub fn new() -> Self {
+        Tabs::with_style(get_style("tab"))
+    }
+    pub fn with_style(style: Arc<StyleAtlas>) -> Self {
+        Tabs {
+            tabs: Vec::new(),
+            style,
+            selected: 0,
+            switched_from: None,
+            hovered: None,
+            page_hovered: false,
+            page_captured: false,
+            header_focused: false,
+            page_focused: false,
+            page_size: Size::ZERO,
+            size: Size::ZERO,
+        }
+    }
+    pub fn tab(mut self, title: impl Into<String>, page: impl Widget<usize> + 'static) -> Self {
+        let label = Label::new(title, None);
+        let x = self.tabs.last().map_or(0.0, |tab|tab.header.0 + tab.header.1);
+        let width = label.width() + 16.0;
+        let mut style = DynamicStyle::new(self.style.clone(), false);
+        style.set_active(self.tabs.len() == self.selected);
+        self.tabs.push(Tab {
+            label,
+            style,
+            page: Box::new(page),
+            header: (x, width),
+        });
+        self
+    }
+
+    fn header_at(&self, x: f64) -> Option<usize> {
+        self.tabs.iter().position(|tab|x >= tab.header.0 && x < tab.header.0 + tab.header.1)
+    }
+
+    fn header_rect(&self) -> Rect {
+        Rect::new(0.0, 0.0, self.size.width, HEADER_HEIGHT)
+    }
+
+    fn select(&self, data: &mut Key<usize>, index: usize) {
+        if index < self.tabs.len() && **data != index {
+            data.change(|selected|*selected = index);
+        }
+    }
+
+    /// Moves the hover between the header buttons
+    fn hover(&mut self, tab: Option<usize>, event: &Event) -> Change {
+        if tab == self.hovered {
+            return Change::None;
+        }
+        let mut change = Change::None;
+        if let Some(old) = self.hovered.take() {
+            if self.tabs[old].style.handle_event(Event::MouseExit).content_change() {
+                change = Change::Content(self.header_rect());
+            }
+        }
+        if let (Some(new), Some(mouse)) = (tab, event.mouse_event()) {
+            if self.tabs[new].style.handle_event(Event::MouseEnter(mouse.clone())).content_change() {
+                change = Change::Content(self.header_rect());
+            }
+        }
+        self.hovered = tab;
+        change
+    }
+
+    fn handle_page_event(&mut self, event: Event, context: WidgetContext, data: Key<usize>) -> EventResponse {
+        let offset = Vec2::new(0.0, HEADER_HEIGHT);
+        match self.tabs.get_mut(self.selected) {
+            Some(tab) => tab.page.handle_event(event, context, data).shift(offset),
+            None => EventResponse::NONE,
+        }
+    }
+
+    fn header_response(change: Change, consumed: bool) -> EventResponse {
+        let response = EventResponse::unchanged(consumed);
+        match change {
+            Change::Content(rect) => response.merge(EventResponse::changed(rect.size(), consumed)),
+            _ => response,
+        }
+    }
+}
+
+impl Widget<usize> for Tabs {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &usize) {
+        if dirty_rect.y0 < HEADER_HEIGHT {
+            for tab in self.tabs.iter_mut() {
+                let style = &tab.style;
+                let label = &mut tab.label;
+                let (x, width) = tab.header;
+                let _ = painter.with_save(|painter|{
+                    painter.transform(Affine::translate(Vec2::new(x, 0.0)));
+                    style.draw(painter, Size::new(width, HEADER_HEIGHT), dirty_rect);
+                    painter.transform(Affine::translate(Vec2::new(4.0, (HEADER_HEIGHT - 17.0) / 2.0)));
+                    label.draw(painter, Size::new(width, HEADER_HEIGHT), dirty_rect, context.id(), &());
+                    Ok(())
+                });
+            }
+            //The active sheet of the selected header hides the focused one, the focus is outlined
+            if let (true, Some(tab), Some(focused)) = (self.header_focused, self.tabs.get(self.selected), &self.style.focused) {
+                let (x, width) = tab.header;
+                let Fill::Solid(color) = &focused.border_fill;
+                let brush = painter.solid_brush(color.clone());
+                painter.stroke(Rect::new(x + 1.0, 1.0, x + width - 1.0, HEADER_HEIGHT - 1.0), &brush, 2.0);
+            }
+        }
+
+        let page_dirty = (dirty_rect - Vec2::new(0.0, HEADER_HEIGHT)).intersect(self.page_size.to_rect());
+        if page_dirty.area() > 0.0 {
+            if let Some(tab) = self.tabs.get_mut(self.selected) {
+                painter.transform(Affine::translate(Vec2::new(0.0, HEADER_HEIGHT)));
+                tab.page.draw(painter, self.page_size, page_dirty, context, data);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<usize>) -> EventResponse {
+        if let Some(mouse) = event.mouse_event() {
+            let pos = mouse.pos;
+            if let Event::MouseDown(_) = event {
+                self.page_captured = false;
+            }
+            if self.page_captured && context.is_pointer_captured() {
+                if let Event::MouseUp(_) = event {
+                    self.page_captured = false;
+                }
+                let page_size = self.page_size;
+                let (response, exited) = match self.tabs.get_mut(self.selected) {
+                    Some(tab) => captured_event(&mut *tab.page, Vec2::new(0.0, HEADER_HEIGHT), page_size, event, context, data),
+                    None => (EventResponse::NONE, false),
+                };
+                if exited {
+                    self.page_hovered = false;
+                }
+                return response;
+            }
+            if pos.y < HEADER_HEIGHT {
+                let tab = self.header_at(pos.x);
+                let mut response = Tabs::header_response(self.hover(tab, &event), false);
+                if self.page_hovered {
+                    self.page_hovered = false;
+                    response = response.merge(self.handle_page_event(Event::MouseExit, context.id(), data.id()));
+                }
+                if let Some(tab) = tab {
+                    let change = self.tabs[tab].style.handle_event(event.clone());
+                    response = response.merge(Tabs::header_response(
+                        if change.content_change() {Change::Content(self.header_rect())} else {Change::None},
+                        false));
+                    if let Event::MouseDown(_) = event {
+                        self.select(&mut data, tab);
+                        response = response.merge(EventResponse::DELETED);
+                    }
+                }
+                return response;
+            }
+
+            let mut response = Tabs::header_response(self.hover(None, &event), false);
+            let mut page_event = event.clone();
+            page_event.shift(Vec2::new(0.0, HEADER_HEIGHT), self.page_size);
+            if !self.page_hovered {
+                self.page_hovered = true;
+                if let Event::MouseMove(mouse) = &page_event {
+                    response = response.merge(self.handle_page_event(Event::MouseEnter(mouse.clone()), context.id(), data.id()));
+                }
+            }
+            let pressed = if let Event::MouseDown(_) = event {true} else {false};
+            let page_response = self.handle_page_event(page_event, context, data);
+            if pressed && !page_response.is_present() {
+                self.page_captured = true;
+            }
+            return response.merge(page_response);
+        }
+
+        match event {
+            Event::MouseExit => {
+                let mut response = Tabs::header_response(self.hover(None, &event), false);
+                if self.page_hovered {
+                    self.page_hovered = false;
+                    response = response.merge(self.handle_page_event(Event::MouseExit, context, data));
+                }
+                response
+            }
+            Event::KeyDown(key_event) if key_event.key_code == KeyCode::Tab && key_event.mods.ctrl => {
+                if !self.tabs.is_empty() {
+                    let count = self.tabs.len();
+                    let next = if key_event.mods.shift {
+                        (self.selected + count - 1) % count
+                    } else {
+                        (self.selected + 1) % count
+                    };
+                    self.select(&mut data, next);
+                }
+                EventResponse::DELETED
+            }
+            Event::AnimFrame(_) => self.handle_page_event(event, context, data),
+            event if event.is_broadcast() => {
+                //Timers and popups are targeted, hidden pages still receive them
+                let mut response = EventResponse::NONE;
+                for (index, tab) in self.tabs.iter_mut().enumerate() {
+                    let page_response = tab.page.handle_event(event.clone(), context.id(), data.id());
+                    if index == self.selected {
+                        response = response.merge(page_response.shift(Vec2::new(0.0, HEADER_HEIGHT)));
+                    }
+                }
+                response
+            }
+            event => self.handle_page_event(event, context, data),
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &usize) -> PrefSize {
+        let header_width = self.tabs.last().map_or(0.0, |tab|tab.header.0 + tab.header.1);
+        let mut pref = match self.tabs.get_mut(self.selected) {
+            Some(tab) => tab.page.get_pref_size(context, data),
+            None => PrefSize::zero(),
+        };
+        pref.min.width = pref.min.width.max(header_width);
+        pref.max.width = pref.max.width.max(header_width);
+        pref.resize(Size::new(0.0, HEADER_HEIGHT));
+        pref
+    }
+
+    fn layout(&mut self, size: Size, mut context: WidgetContext, data: &usize) {
+        self.size = size;
+        let page_size = Size::new(size.width, (size.height - HEADER_HEIGHT).max(0.0));
+        let resized = page_size != self.page_size;
+        self.page_size = page_size;
+
+        //update has no context, the pages are switched at the next layout
+        if let Some(old) = self.switched_from.take() {
+            if let Some(tab) = self.tabs.get_mut(old) {
+                tab.page.lifecycle(&Lifecycle::Hidden, context.id());
+            }
+            if let Some(tab) = self.tabs.get_mut(self.selected) {
+                tab.page.lifecycle(&Lifecycle::Shown, context.id());
+                tab.page.lifecycle(&Lifecycle::SizeChanged(page_size), context.id());
+            }
+        } else if resized {
+            if let Some(tab) = self.tabs.get_mut(self.selected) {
+                tab.page.lifecycle(&Lifecycle::SizeChanged(page_size), context.id());
+            }
+        }
+
+        if let Some(tab) = self.tabs.get_mut(self.selected) {
+            tab.page.layout(page_size, context, data);
+        }
+    }
+
+    fn build(&mut self, mut context: WidgetContext) {
+        for tab in self.tabs.iter_mut() {
+            tab.page.build(context.id());
+        }
+    }
+
+    fn update(&mut self, new: &usize, old: Option<&usize>) -> Change {
+        let mut change = Change::None;
+        for tab in self.tabs.iter_mut() {
+            change = change.merge(tab.page.update(new, old));
+        }
+        if *new != self.selected && *new < self.tabs.len() {
+            if let Some(tab) = self.tabs.get_mut(self.selected) {
+                tab.style.set_active(false);
+                tab.style.set_focused(false);
+            }
+            self.tabs[*new].style.set_active(true);
+            self.tabs[*new].style.set_focused(self.header_focused);
+            //A switch back before the next layout cancels the pending one
+            self.switched_from = match self.switched_from {
+                Some(old) if old == *new => None,
+                Some(old) => Some(old),
+                None => Some(self.selected),
+            };
+            self.selected = *new;
+            return Change::Bounds;
+        }
+        change
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        let selected = self.selected;
+        let tab = match self.tabs.get_mut(selected) {
+            Some(tab) => tab,
+            None => return false,
+        };
+        if self.header_focused {
+            self.header_focused = false;
+            tab.style.set_focused(false);
+            return false;
+        }
+        if tab.page.traverse_focus(context) {
+            self.page_focused = true;
+            return true;
+        }
+        if self.page_focused {
+            self.page_focused = false;
+            return false;
+        }
+        //The page declined the focus, the header takes it to receive Ctrl+Tab
+        self.header_focused = true;
+        tab.style.set_focused(true);
+        true
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        match event {
+            Lifecycle::WidgetAdded | Lifecycle::WidgetRemoved => {
+                for (index, tab) in self.tabs.iter_mut().enumerate() {
+                    tab.page.lifecycle(event, context.id());
+                    if *event == Lifecycle::WidgetAdded && index != self.selected {
+                        tab.page.lifecycle(&Lifecycle::Hidden, context.id());
+                    }
+                }
+            }
+            Lifecycle::Shown | Lifecycle::Hidden => {
+                if let Some(tab) = self.tabs.get_mut(self.selected) {
+                    tab.page.lifecycle(event, context);
+                }
+            }
+            Lifecycle::SizeChanged(_) => {}
+        }
+    }
+}
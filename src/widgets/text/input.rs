@@ -1,18 +1,25 @@
-use crate::widgets::text::Label;
+use crate::widgets::text::{Label, TEXT_PADDING};
 use crate::widgets::Widget;
-use druid_shell::kurbo::{Rect, Size};
-use druid_shell::piet::Piet;
+use druid_shell::kurbo::{Rect, Size, Line};
+use druid_shell::piet::{Piet, RenderContext, Color};
 use crate::state::key::Key;
 use druid_shell::KeyCode;
 use crate::app::widget_graph::WidgetContext;
 use crate::app::event::{EventResponse, Event, Change};
 use crate::app::size::PrefSize;
+use crate::app::timer::TimerToken;
+use std::time::Duration;
+
+const CARET_BLINK: Duration = Duration::from_millis(500);
 
 pub struct TextInput{
     text: Label,
     //Measured in chars
     cursor_position: usize,
     focused: bool,
+    caret_visible: bool,
+    blink: Option<TimerToken>,
+    size: Size,
 }
 
 impl TextInput {
@@ -21,16 +28,33 @@ impl TextInput {
             text: Label::new("", None),
             cursor_position: 0,
             focused: false,
+            caret_visible: false,
+            blink: None,
+            size: Size::ZERO,
         }
     }
 }
 
 impl Widget<String> for TextInput {
     fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &String) {
-        self.text.draw(painter, size, dirty_rect, context, data)
+        self.text.draw(painter, size, dirty_rect, context, data);
+
+        if self.focused && self.caret_visible {
+            //The caret follows the end of the text
+            let x = TEXT_PADDING + self.text.width();
+            let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+            painter.stroke(Line::new((x, 2.0), (x, size.height - 2.0)), &brush, 1.0);
+        }
     }
 
-    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<String>) -> EventResponse {
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, mut data: Key<String>) -> EventResponse {
+        if let Event::Timer(token) = event {
+            if self.blink == Some(token) {
+                self.caret_visible = !self.caret_visible;
+                self.blink = Some(context.request_timer(CARET_BLINK));
+                return EventResponse::changed(self.size, true);
+            }
+        }
         if let Event::KeyDown(key_event) = event {
             match key_event.key_code {
                 KeyCode::Backspace => {
@@ -57,7 +81,7 @@ impl Widget<String> for TextInput {
     }
 
     fn layout(&mut self, size: Size, context: WidgetContext, data: &String) {
-
+        self.size = size;
     }
 
     fn build(&mut self, context: WidgetContext) {
@@ -69,8 +93,14 @@ impl Widget<String> for TextInput {
         self.text.recalc_text();
         Change::Bounds
     }
-    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+    fn traverse_focus(&mut self, mut context: WidgetContext) -> bool {
         self.focused = !self.focused;
+        if self.focused {
+            self.caret_visible = true;
+            self.blink = Some(context.request_timer(CARET_BLINK));
+        } else if let Some(blink) = self.blink.take() {
+            context.cancel_timer(blink);
+        }
         println!("{}focused textfield!", if self.focused {""} else {"un"});
         self.focused
     }
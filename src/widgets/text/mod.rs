@@ -1,4 +1,5 @@
 mod input;
+mod number;
 
 use crate::widgets::Widget;
 use druid_shell::kurbo::{Rect, Size};
@@ -8,6 +9,7 @@ use crate::widgets::widget::IntoWidget;
 use std::marker::PhantomData;
 
 pub use input::TextInput;
+pub use number::NumberInput;
 use crate::app::widget_graph::WidgetContext;
 use crate::app::event::{EventResponse, Event, Change};
 use crate::app::size::PrefSize;
@@ -28,6 +30,9 @@ impl<T: Clone> IntoWidget<T> for &str {
     }
 }
 
+/// The horizontal space on both sides of the text of a Label
+pub(crate) const TEXT_PADDING: f64 = 4.0;
+
 pub struct Label {
     text: String,
     font: PietFont,
@@ -51,12 +56,16 @@ impl Label {
     pub fn recalc_text(&mut self) {
         self.layout = PietText::new().new_text_layout(&self.font, &self.text, None).build().unwrap();
     }
+    /// The width of the laid out text
+    pub fn width(&self) -> f64 {
+        self.layout.width()
+    }
 }
 
 impl<T: Clone> Widget<T> for Label {
     fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
         let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
-        painter.draw_text(&self.layout, (4.0, 13.0), &brush);
+        painter.draw_text(&self.layout, (TEXT_PADDING, 13.0), &brush);
     }
 
     fn handle_event(&mut self, event: Event, context: WidgetContext, data: Key<T>) -> EventResponse {
@@ -64,7 +73,7 @@ impl<T: Clone> Widget<T> for Label {
     }
 
     fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
-        PrefSize::fixed(Size::new(self.layout.width() + 8.0, 17.0))
+        PrefSize::fixed(Size::new(self.layout.width() + 2.0 * TEXT_PADDING, 17.0))
     }
 
     fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
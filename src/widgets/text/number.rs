@@ -0,0 +1,119 @@
+use crate::widgets::text::TextInput;
+use crate::widgets::Widget;
+use druid_shell::kurbo::{Rect, Size, Line};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use std::str::FromStr;
+use std::fmt::Display;
+
+/// A TextInput for numbers
+/// Only characters of numbers can be typed, the value is committed whenever the text parses and
+/// lies in the range. Invalid text is underlined and keeps the last valid value.
+pub struct NumberInput<T> {
+    input: TextInput,
+    text: String,
+    min: Option<T>,
+    max: Option<T>,
+    valid: bool,
+    size: Size,
+}
+
+impl<T: Clone + PartialOrd + FromStr + Display> NumberInput<T> {
+    pub fn new() -> Self {
+        NumberInput {
+            input: TextInput::new(),
+            text: String::new(),
+            min: None,
+            max: None,
+            valid: true,
+            size: Size::ZERO,
+        }
+    }
+    pub fn range(mut self, min: T, max: T) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    fn parse(&self) -> Option<T> {
+        let value = T::from_str(self.text.trim()).ok()?;
+        let too_small = self.min.as_ref().map_or(false, |min|value < *min);
+        let too_big = self.max.as_ref().map_or(false, |max|value > *max);
+        if too_small || too_big {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl<T: Clone + PartialOrd + FromStr + Display> Widget<T> for NumberInput<T> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.input.draw(painter, size, dirty_rect, context, &self.text);
+
+        if !self.valid {
+            let brush = painter.solid_brush(Color::rgb8(220, 60, 60));
+            painter.stroke(Line::new((2.0, size.height - 1.0), (size.width - 2.0, size.height - 1.0)), &brush, 1.0);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<T>) -> EventResponse {
+        if let Event::KeyDown(key_event) = &event {
+            if let Some(text) = key_event.text() {
+                let numeric = text.chars().all(|c|c.is_ascii_digit() || "+-.eE".contains(c) || c.is_control());
+                if !numeric {
+                    return EventResponse::DELETED;
+                }
+            }
+        }
+
+        let mut changed = false;
+        let response = self.input.handle_event(event, context, Key::new(&mut self.text, &mut changed));
+        if !changed {
+            return response;
+        }
+
+        self.input.update(&self.text, None);
+        match self.parse() {
+            Some(value) => {
+                self.valid = true;
+                data.change(|current|*current = value);
+            }
+            None => {
+                self.valid = false;
+            }
+        }
+        response.merge(EventResponse::bounds_changed(false))
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.input.get_pref_size(context, &self.text)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.size = size;
+        self.input.layout(size, context, &self.text)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        Widget::<String>::build(&mut self.input, context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        //Keep the text while it describes the value, e.g. "1.0" or "1."
+        if self.parse().map_or(true, |value|value != *new) {
+            self.text = new.to_string();
+            self.valid = true;
+            self.input.update(&self.text, None)
+        } else {
+            Change::None
+        }
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        Widget::<String>::traverse_focus(&mut self.input, context)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::widgets::Widget;
+use crate::widgets::text::Label;
+use crate::widgets::style::{Style, StyleAtlas, DynamicStyle, get_style};
+use crate::widgets::raw::Wrapper;
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, BezPath, Circle};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::KeyCode;
+use std::sync::Arc;
+
+/// The clickable part of a Checkbox, Switch or radio button, followed by a Label
+pub(crate) struct Toggle {
+    style: DynamicStyle,
+    checked_style: Option<Style>,
+    checked: bool,
+    label: Label,
+    indicator: Size,
+    pressed: bool,
+    focused: bool,
+    size: Size,
+}
+
+impl Toggle {
+    pub(crate) fn new(label: impl Into<String>, style: Arc<StyleAtlas>, indicator: Size) -> Self {
+        //The active sheet is drawn inside the border of the current sheet, so hover and focus
+        //stay visible while checked
+        let checked_style = style.active.clone().map(|mut checked|{
+            checked.margin += 2.0;
+            checked.corners = (checked.corners - 2.0).max(0.0);
+            checked
+        });
+        Toggle {
+            style: DynamicStyle::new(style, false),
+            checked_style,
+            checked: false,
+            label: Label::new(label, None),
+            indicator,
+            pressed: false,
+            focused: false,
+            size: Size::ZERO,
+        }
+    }
+
+    /// Draws the indicator and the label, draw_mark paints the content of the indicator
+    pub(crate) fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, draw_mark: impl FnOnce(&mut Piet, Size)) {
+        let top = ((size.height - self.indicator.height) / 2.0).max(0.0);
+        let indicator = self.indicator;
+        let style = &self.style;
+        let checked_style = self.checked_style.as_ref().filter(|_|self.checked);
+        let _ = painter.with_save(|painter|{
+            painter.transform(Affine::translate(Vec2::new(2.0, top)));
+            style.draw(painter, indicator, dirty_rect);
+            if let Some(checked_style) = checked_style {
+                checked_style.draw(painter, indicator);
+            }
+            draw_mark(painter, indicator);
+            Ok(())
+        });
+        painter.transform(Affine::translate(Vec2::new(indicator.width + 4.0, 0.0)));
+        self.label.draw(painter, size, dirty_rect, context, &());
+    }
+
+    /// Handles hover and press visuals, returns the response and true if the Toggle was activated
+    /// by a click or by Space while focused
+    pub(crate) fn handle_event(&mut self, event: Event) -> (EventResponse, bool) {
+        let changed = self.style.handle_event(event.clone()).content_change();
+        let mut activated = false;
+        match event {
+            Event::MouseDown(_) => {
+                self.pressed = true;
+            }
+            Event::MouseExit => {
+                self.pressed = false;
+            }
+            Event::MouseUp(_) => {
+                activated = self.pressed;
+                self.pressed = false;
+            }
+            Event::KeyDown(key_event) => {
+                activated = self.focused && key_event.key_code == KeyCode::Space;
+            }
+            _ => {}
+        }
+        let consumed = activated || self.pressed;
+        let response = if changed {
+            EventResponse::changed(self.size, consumed)
+        } else {
+            EventResponse::unchanged(consumed)
+        };
+        (response, activated)
+    }
+
+    pub(crate) fn pref_size(&self) -> PrefSize {
+        PrefSize::fixed(Size::new(self.indicator.width + self.label.width() + 14.0, self.indicator.height.max(17.0) + 2.0))
+    }
+
+    pub(crate) fn layout(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    /// Draws the active sheet inside the indicator while checked
+    pub(crate) fn set_checked(&mut self, checked: bool) -> Change {
+        self.checked = checked;
+        Change::Content(self.size.to_rect())
+    }
+
+    pub(crate) fn traverse_focus(&mut self) -> bool {
+        self.focused = !self.focused;
+        self.style.set_focused(self.focused);
+        self.focused
+    }
+}
+
+/// A box with a label, which toggles a bool when clicked or when Space is pressed
+pub struct Checkbox {
+    toggle: Toggle,
+}
+
+impl Checkbox {
+    pub fn new(label: impl Into<String>) -> Self {
+        Checkbox::with_style(label, get_style("checkbox"))
+    }
+    pub fn with_style(label: impl Into<String>, style: Arc<StyleAtlas>) -> Self {
+        Checkbox {
+            toggle: Toggle::new(label, style, Size::new(16.0, 16.0)),
+        }
+    }
+}
+
+impl Widget<bool> for Checkbox {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &bool) {
+        let checked = *data;
+        self.toggle.draw(painter, size, dirty_rect, context, |painter, size|{
+            if checked {
+                let mut mark = BezPath::new();
+                mark.move_to((size.width * 0.25, size.height * 0.5));
+                mark.line_to((size.width * 0.45, size.height * 0.7));
+                mark.line_to((size.width * 0.75, size.height * 0.3));
+                let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+                painter.stroke(mark, &brush, 2.0);
+            }
+        });
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<bool>) -> EventResponse {
+        let (response, activated) = self.toggle.handle_event(event);
+        if activated {
+            data.change(|checked|*checked = !*checked);
+        }
+        response
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &bool) -> PrefSize {
+        self.toggle.pref_size()
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &bool) {
+        self.toggle.layout(size)
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &bool, old: Option<&bool>) -> Change {
+        self.toggle.set_checked(*new)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.toggle.traverse_focus()
+    }
+}
+
+/// A sliding switch with a label, which toggles a bool when clicked or when Space is pressed
+pub struct Switch {
+    toggle: Toggle,
+}
+
+impl Switch {
+    pub fn new(label: impl Into<String>) -> Self {
+        Switch::with_style(label, get_style("switch"))
+    }
+    pub fn with_style(label: impl Into<String>, style: Arc<StyleAtlas>) -> Self {
+        Switch {
+            toggle: Toggle::new(label, style, Size::new(30.0, 16.0)),
+        }
+    }
+}
+
+impl Widget<bool> for Switch {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &bool) {
+        let on = *data;
+        self.toggle.draw(painter, size, dirty_rect, context, |painter, size|{
+            let radius = size.height / 2.0;
+            let x = if on {size.width - radius} else {radius};
+            let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+            painter.fill(Circle::new((x, radius), radius - 3.0), &brush);
+        });
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<bool>) -> EventResponse {
+        let (response, activated) = self.toggle.handle_event(event);
+        if activated {
+            data.change(|on|*on = !*on);
+        }
+        response
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &bool) -> PrefSize {
+        self.toggle.pref_size()
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &bool) {
+        self.toggle.layout(size)
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &bool, old: Option<&bool>) -> Change {
+        self.toggle.set_checked(*new)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.toggle.traverse_focus()
+    }
+}
@@ -0,0 +1,195 @@
+use crate::widgets::Widget;
+use crate::state::key::Key;
+use crate::app::widget_graph::{WidgetContext, WidgetGraph};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
+use crate::app::size::PrefSize;
+use crate::app::overlay::{OverlayToken, OverlayPainter};
+use crate::app::timer::TimerToken;
+use druid_shell::kurbo::{Rect, Size, Vec2};
+use druid_shell::piet::{Piet, RenderContext, Color, Text, TextLayout, FontBuilder, TextLayoutBuilder, PietText};
+use std::sync::Arc;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+/// The distance of the tooltip to the mouse
+const CURSOR_OFFSET: (f64, f64) = (12.0, 18.0);
+
+/// The content of a tooltip
+#[derive(Clone)]
+pub enum TooltipContent {
+    Text(String),
+    /// Paints the tooltip, the origin is its top left corner
+    Custom(Size, Arc<dyn Fn(&mut Piet, Size)>),
+    /// A Widget in its own WidgetGraph with its minimal size
+    /// The Widget doesn't receive events and changes of its States aren't drawn.
+    Widget(Rc<RefCell<WidgetGraph>>),
+}
+
+impl TooltipContent {
+    pub fn custom(size: Size, painter: impl Fn(&mut Piet, Size) + 'static) -> Self {
+        TooltipContent::Custom(size, Arc::new(painter))
+    }
+    pub fn widget(widget: impl Widget<()> + 'static) -> Self {
+        TooltipContent::Widget(Rc::new(RefCell::new(WidgetGraph::new(widget))))
+    }
+
+    fn size(&self) -> Size {
+        match self {
+            TooltipContent::Text(text) => {
+                let mut piet_text = PietText::new();
+                let font = piet_text.new_font_by_name("System", 13.0).build().unwrap();
+                let layout = piet_text.new_text_layout(&font, text, None).build().unwrap();
+                Size::new(layout.width() + 12.0, 20.0)
+            }
+            TooltipContent::Custom(size, _) => *size,
+            TooltipContent::Widget(graph) => graph.borrow_mut().pref_size().min,
+        }
+    }
+
+    fn painter(&self) -> OverlayPainter {
+        match self.clone() {
+            TooltipContent::Text(text) => Box::new(move|painter: &mut Piet, size: Size|{
+                let background = painter.solid_brush(Color::grey8(40));
+                painter.fill(size.to_rect(), &background);
+
+                let mut piet_text = PietText::new();
+                let font = piet_text.new_font_by_name("System", 13.0).build().unwrap();
+                let layout = piet_text.new_text_layout(&font, &text, None).build().unwrap();
+                let brush = painter.solid_brush(Color::rgb8(255, 255, 255));
+                painter.draw_text(&layout, (6.0, 14.0), &brush);
+
+                let border = painter.solid_brush(Color::grey8(120));
+                painter.stroke(Rect::new(0.5, 0.5, size.width - 0.5, size.height - 0.5), &border, 1.0);
+            }),
+            TooltipContent::Custom(_, painter) => Box::new(move|piet: &mut Piet, size: Size|painter(piet, size)),
+            TooltipContent::Widget(graph) => Box::new(move|piet: &mut Piet, size: Size|{
+                let mut graph = graph.borrow_mut();
+                graph.layout(size);
+                graph.draw_widgets(piet, size, size.to_rect());
+            }),
+        }
+    }
+}
+
+impl From<&str> for TooltipContent {
+    fn from(text: &str) -> Self {
+        TooltipContent::Text(text.to_string())
+    }
+}
+
+impl From<String> for TooltipContent {
+    fn from(text: String) -> Self {
+        TooltipContent::Text(text)
+    }
+}
+
+/// Shows a tooltip near the mouse after it hovered over the Widget for a while
+/// The tooltip is hidden when the mouse leaves the Widget, on a click or on a key press.
+pub struct Tooltip<W> {
+    widget: W,
+    content: TooltipContent,
+    delay: Duration,
+    /// the time of the last mouse move, the timer is requested again until it fires a delay later
+    last_move: Instant,
+    timer: Option<TimerToken>,
+    shown: Option<OverlayToken>,
+}
+
+impl<W> Tooltip<W> {
+    pub fn new(widget: W, content: impl Into<TooltipContent>) -> Self {
+        Tooltip {
+            widget,
+            content: content.into(),
+            delay: TOOLTIP_DELAY,
+            last_move: Instant::now(),
+            timer: None,
+            shown: None,
+        }
+    }
+    /// The time the mouse has to hover over the Widget
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn show(&mut self, context: &mut WidgetContext) {
+        let rect = Rect::from_origin_size(context.mouse_pos() + Vec2::from(CURSOR_OFFSET), self.content.size());
+        self.shown = Some(context.show_tooltip(rect, self.content.painter()));
+    }
+
+    fn hide(&mut self, context: &mut WidgetContext) {
+        if let Some(timer) = self.timer.take() {
+            context.cancel_timer(timer);
+        }
+        if let Some(token) = self.shown.take() {
+            context.hide_tooltip(token);
+        }
+    }
+}
+
+impl<T: Clone, W: Widget<T>> Widget<T> for Tooltip<W> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, context: WidgetContext, data: &T) {
+        self.widget.draw(painter, size, dirty_rect, context, data)
+    }
+
+    fn handle_event(&mut self, event: Event, mut context: WidgetContext, data: Key<T>) -> EventResponse {
+        let response = self.widget.handle_event(event.clone(), context.id(), data);
+        match event {
+            Event::MouseEnter(_) | Event::MouseMove(_) => {
+                //The delay starts again while the mouse moves
+                self.last_move = Instant::now();
+                if self.shown.is_none() && self.timer.is_none() {
+                    self.timer = Some(context.request_timer(self.delay));
+                }
+            }
+            Event::Timer(token) if self.timer == Some(token) => {
+                let elapsed = self.last_move.elapsed();
+                if elapsed >= self.delay {
+                    self.timer = None;
+                    self.show(&mut context);
+                } else {
+                    self.timer = Some(context.request_timer(self.delay - elapsed));
+                }
+            }
+            Event::MouseDown(_) | Event::Wheel(_) | Event::KeyDown(_) | Event::MouseExit => {
+                self.hide(&mut context);
+            }
+            //The window hid the tooltip, e.g. on a key press which wasn't routed to this Widget
+            Event::OverlayClosed(token) if self.shown == Some(token) => {
+                self.shown = None;
+            }
+            _ => {}
+        }
+        response
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &T) -> PrefSize {
+        self.widget.get_pref_size(context, data)
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &T) {
+        self.widget.layout(size, context, data)
+    }
+
+    fn build(&mut self, context: WidgetContext) {
+        self.widget.build(context)
+    }
+
+    fn update(&mut self, new: &T, old: Option<&T>) -> Change {
+        self.widget.update(new, old)
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.widget.traverse_focus(context)
+    }
+
+    fn lifecycle(&mut self, event: &Lifecycle, mut context: WidgetContext) {
+        match event {
+            Lifecycle::Hidden | Lifecycle::WidgetRemoved => self.hide(&mut context),
+            _ => {}
+        }
+        self.widget.lifecycle(event, context)
+    }
+}
@@ -0,0 +1,360 @@
+use crate::widgets::Widget;
+use crate::widgets::scrollbar::{self, ThumbDrag};
+use crate::widgets::text::Label;
+use crate::widgets::style::{StyleAtlas, get_style};
+use crate::state::key::Key;
+use crate::app::widget_graph::WidgetContext;
+use crate::app::event::{EventResponse, Event, Change};
+use crate::app::size::PrefSize;
+use druid_shell::kurbo::{Rect, Size, Affine, Vec2, Point, BezPath};
+use druid_shell::piet::{Piet, RenderContext, Color};
+use druid_shell::KeyCode;
+use std::collections::{BTreeSet, HashMap};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+const INDENT: f64 = 16.0;
+const TOGGLE_WIDTH: f64 = 16.0;
+
+/// A node of the data shown by a TreeView
+///
+/// The children are only requested for expanded nodes, whenever the data changes. Nodes can
+/// load them lazily, e.g. the entries of a directory, and should cache them if loading is slow.
+pub trait TreeNode: Clone + 'static {
+    /// Identifies the node in the expansion state and the selection, it has to be unique and
+    /// should stay the same when the tree changes
+    type Id: Clone + Ord + 'static;
+
+    fn id(&self) -> Self::Id;
+    fn label(&self) -> String;
+    fn children(&self) -> Cow<[Self]>;
+    /// Returns true if the node can be expanded, without requesting its children
+    fn has_children(&self) -> bool {
+        !self.children().is_empty()
+    }
+}
+
+/// The nodes of a tree with the expanded and the selected nodes
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeData<N: TreeNode> {
+    pub roots: Vec<N>,
+    pub expanded: BTreeSet<N::Id>,
+    pub selected: Option<N::Id>,
+}
+
+impl<N: TreeNode> TreeData<N> {
+    pub fn new(roots: Vec<N>) -> Self {
+        TreeData {
+            roots,
+            expanded: BTreeSet::new(),
+            selected: None,
+        }
+    }
+}
+
+/// A visible node
+struct Line<Id> {
+    id: Id,
+    label: String,
+    depth: usize,
+    parent: Option<usize>,
+    expandable: bool,
+}
+
+/// Shows a tree of nodes with a toggle to expand and collapse each node with children
+///
+/// Up and Down move the selection, Right expands the selected node or moves to its first child,
+/// Left collapses it or moves to its parent and Space toggles it. Only the labels of the visible
+/// rows are laid out.
+pub struct TreeView<N: TreeNode> {
+    lines: Vec<Line<N::Id>>,
+    labels: HashMap<usize, Label>,
+    row_height: f64,
+    style: Arc<StyleAtlas>,
+    offset: f64,
+    thumb: ThumbDrag,
+    hovered: Option<usize>,
+    focused: bool,
+    size: Size,
+}
+
+impl<N: TreeNode> TreeView<N> {
+    pub fn new() -> Self {
+        TreeView {
+            lines: Vec::new(),
+            labels: HashMap::new(),
+            row_height: 20.0,
+            style: get_style("list_row"),
+            offset: 0.0,
+            thumb: ThumbDrag::new(),
+            hovered: None,
+            focused: false,
+            size: Size::ZERO,
+        }
+    }
+    pub fn row_height(mut self, height: f64) -> Self {
+        self.row_height = height.max(1.0);
+        self
+    }
+    pub fn with_style(mut self, style: Arc<StyleAtlas>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Collects the nodes whose ancestors are all expanded
+    fn flatten(&mut self, data: &TreeData<N>) {
+        fn visit<N: TreeNode>(lines: &mut Vec<Line<N::Id>>, nodes: &[N], depth: usize, parent: Option<usize>, expanded: &BTreeSet<N::Id>) {
+            for node in nodes.iter() {
+                let id = node.id();
+                let expandable = node.has_children();
+                let open = expandable && expanded.contains(&id);
+                let line = lines.len();
+                lines.push(Line {
+                    id,
+                    label: node.label(),
+                    depth,
+                    parent,
+                    expandable,
+                });
+                if open {
+                    visit(lines, &node.children(), depth + 1, Some(line), expanded);
+                }
+            }
+        }
+        self.lines.clear();
+        visit(&mut self.lines, &data.roots, 0, None, &data.expanded);
+        self.labels.clear();
+        self.clamp_offset();
+    }
+
+    fn clamp_offset(&mut self) {
+        let max = (self.lines.len() as f64 * self.row_height - self.size.height).max(0.0);
+        self.offset = self.offset.min(max).max(0.0);
+    }
+
+    fn scroll_to(&mut self, line: usize) {
+        let top = line as f64 * self.row_height;
+        if top < self.offset {
+            self.offset = top;
+        } else if top + self.row_height > self.offset + self.size.height {
+            self.offset = top + self.row_height - self.size.height;
+        }
+    }
+
+    fn line_at(&self, pos: Point) -> Option<usize> {
+        let line = ((pos.y + self.offset) / self.row_height).floor();
+        if pos.y < 0.0 || line < 0.0 || line as usize >= self.lines.len() {
+            None
+        } else {
+            Some(line as usize)
+        }
+    }
+
+    fn selected_line(&self, data: &TreeData<N>) -> Option<usize> {
+        let selected = data.selected.as_ref()?;
+        self.lines.iter().position(|line|line.id == *selected)
+    }
+
+    fn select(&mut self, data: &mut Key<TreeData<N>>, line: usize) -> EventResponse {
+        if let Some(id) = self.lines.get(line).map(|line|line.id.clone()) {
+            if data.selected.as_ref() != Some(&id) {
+                data.change(|tree|tree.selected = Some(id));
+            }
+            self.scroll_to(line);
+        }
+        EventResponse::changed(self.size, true)
+    }
+
+    /// Collapsing a node moves the selection from its hidden descendants to the node
+    fn set_expanded(&self, data: &mut Key<TreeData<N>>, index: usize, expanded: bool) -> EventResponse {
+        let line = &self.lines[index];
+        if line.expandable && data.expanded.contains(&line.id) != expanded {
+            let id = line.id.clone();
+            let depth = line.depth;
+            let selection_hidden = !expanded && self.lines[index + 1..].iter()
+                .take_while(|other|other.depth > depth)
+                .any(|other|data.selected.as_ref() == Some(&other.id));
+            data.change(|tree|if expanded {
+                tree.expanded.insert(id);
+            } else {
+                if selection_hidden {
+                    tree.selected = Some(id.clone());
+                }
+                tree.expanded.remove(&id);
+            });
+        }
+        EventResponse::DELETED
+    }
+
+    fn page_rows(&self) -> usize {
+        ((self.size.height / self.row_height) as usize).max(1)
+    }
+}
+
+impl<N: TreeNode> Widget<TreeData<N>> for TreeView<N> {
+    fn draw(&mut self, painter: &mut Piet, size: Size, dirty_rect: Rect, mut context: WidgetContext, data: &TreeData<N>) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let first = (self.offset / self.row_height).floor() as usize;
+        let last = (((self.offset + size.height) / self.row_height).ceil() as usize).min(self.lines.len());
+        self.labels.retain(|line, _|*line >= first && *line < last);
+
+        let selected = self.selected_line(data);
+        let _ = painter.with_save(|painter|{
+            painter.clip(size.to_rect());
+            for index in first..last {
+                let y = index as f64 * self.row_height - self.offset;
+                let row = Rect::new(0.0, y, size.width, y + self.row_height);
+                if row.intersect(dirty_rect).area() <= 0.0 {
+                    continue;
+                }
+                let line = &self.lines[index];
+                let style = if selected == Some(index) {
+                    self.style.active.as_ref()
+                } else if self.hovered == Some(index) {
+                    self.style.hovered.as_ref()
+                } else {
+                    None
+                }.unwrap_or(&self.style.idle);
+                let x = line.depth as f64 * INDENT;
+                let expanded = data.expanded.contains(&line.id);
+                let label = self.labels.entry(index)
+                    .or_insert_with(||Label::new(line.label.as_str(), None));
+                let row_height = self.row_height;
+
+                let _ = painter.with_save(|painter|{
+                    painter.transform(Affine::translate(Vec2::new(0.0, y)));
+                    style.draw(painter, row.size());
+
+                    if line.expandable {
+                        let center = Point::new(x + TOGGLE_WIDTH / 2.0, row_height / 2.0);
+                        let mut arrow = BezPath::new();
+                        if expanded {
+                            arrow.move_to(center + Vec2::new(-4.0, -2.0));
+                            arrow.line_to(center + Vec2::new(4.0, -2.0));
+                            arrow.line_to(center + Vec2::new(0.0, 3.0));
+                        } else {
+                            arrow.move_to(center + Vec2::new(-2.0, -4.0));
+                            arrow.line_to(center + Vec2::new(3.0, 0.0));
+                            arrow.line_to(center + Vec2::new(-2.0, 4.0));
+                        }
+                        arrow.close_path();
+                        let brush = painter.solid_brush(Color::grey8(180));
+                        painter.fill(arrow, &brush);
+                    }
+
+                    painter.transform(Affine::translate(Vec2::new(x + TOGGLE_WIDTH, (row_height - 17.0) / 2.0)));
+                    label.draw(painter, row.size(), row.size().to_rect(), context.id(), &());
+                    Ok(())
+                });
+            }
+            Ok(())
+        });
+
+        scrollbar::draw(painter, size.to_rect(), self.lines.len() as f64 * self.row_height, self.offset);
+    }
+
+    fn handle_event(&mut self, event: Event, context: WidgetContext, mut data: Key<TreeData<N>>) -> EventResponse {
+        let total = self.lines.len() as f64 * self.row_height;
+        if let Some(offset) = self.thumb.handle_event(&event, self.size.to_rect(), total, self.offset) {
+            self.offset = offset;
+            return EventResponse::changed(self.size, true);
+        }
+        match event {
+            Event::MouseEnter(mouse) | Event::MouseMove(mouse) => {
+                let line = self.line_at(mouse.pos);
+                if line != self.hovered {
+                    self.hovered = line;
+                    return EventResponse::changed(self.size, false);
+                }
+                EventResponse::NONE
+            }
+            Event::MouseExit => {
+                if self.hovered.take().is_some() {
+                    return EventResponse::changed(self.size, false);
+                }
+                EventResponse::NONE
+            }
+            Event::MouseDown(mouse) => {
+                let line = match self.line_at(mouse.pos) {
+                    Some(line) => line,
+                    None => return EventResponse::NONE,
+                };
+                let x = self.lines[line].depth as f64 * INDENT;
+                let on_toggle = mouse.pos.x >= x && mouse.pos.x < x + TOGGLE_WIDTH;
+                if on_toggle || mouse.count >= 2 {
+                    let expanded = data.expanded.contains(&self.lines[line].id);
+                    let _ = self.set_expanded(&mut data, line, !expanded);
+                }
+                if !on_toggle {
+                    return self.select(&mut data, line);
+                }
+                EventResponse::DELETED
+            }
+            Event::Wheel(mouse) => {
+                let offset = self.offset;
+                self.offset += mouse.wheel_delta.y;
+                self.clamp_offset();
+                if (self.offset - offset).abs() > std::f64::EPSILON {
+                    self.hovered = self.line_at(mouse.pos);
+                    return EventResponse::changed(self.size, true);
+                }
+                EventResponse::NONE
+            }
+            Event::KeyDown(key_event) if self.focused => {
+                let last = self.lines.len().saturating_sub(1);
+                let selected = match self.selected_line(&data) {
+                    Some(line) => line,
+                    None => return self.select(&mut data, 0),
+                };
+                let expandable = self.lines[selected].expandable;
+                let expanded = data.expanded.contains(&self.lines[selected].id);
+                match key_event.key_code {
+                    KeyCode::ArrowDown => self.select(&mut data, (selected + 1).min(last)),
+                    KeyCode::ArrowUp => self.select(&mut data, selected.saturating_sub(1)),
+                    KeyCode::PageDown => self.select(&mut data, (selected + self.page_rows()).min(last)),
+                    KeyCode::PageUp => self.select(&mut data, selected.saturating_sub(self.page_rows())),
+                    KeyCode::Home => self.select(&mut data, 0),
+                    KeyCode::End => self.select(&mut data, last),
+                    KeyCode::ArrowRight if expandable && !expanded => self.set_expanded(&mut data, selected, true),
+                    //The first child follows its expanded parent
+                    KeyCode::ArrowRight if expandable => self.select(&mut data, (selected + 1).min(last)),
+                    KeyCode::ArrowLeft if expandable && expanded => self.set_expanded(&mut data, selected, false),
+                    KeyCode::ArrowLeft => match self.lines[selected].parent {
+                        Some(parent) => self.select(&mut data, parent),
+                        None => EventResponse::DELETED,
+                    },
+                    KeyCode::Space | KeyCode::Return => self.set_expanded(&mut data, selected, !expanded),
+                    _ => EventResponse::NONE,
+                }
+            }
+            _ => EventResponse::NONE,
+        }
+    }
+
+    fn get_pref_size(&mut self, context: WidgetContext, data: &TreeData<N>) -> PrefSize {
+        PrefSize::flexible(Size::new(150.0, self.row_height * 8.0))
+    }
+
+    fn layout(&mut self, size: Size, context: WidgetContext, data: &TreeData<N>) {
+        self.size = size;
+        self.clamp_offset();
+    }
+
+    fn build(&mut self, context: WidgetContext) {}
+
+    fn update(&mut self, new: &TreeData<N>, old: Option<&TreeData<N>>) -> Change {
+        self.flatten(new);
+        if self.hovered.map_or(false, |line|line >= self.lines.len()) {
+            self.hovered = None;
+        }
+        Change::Content(self.size.to_rect())
+    }
+
+    fn traverse_focus(&mut self, context: WidgetContext) -> bool {
+        self.focused = !self.focused;
+        self.focused
+    }
+}
@@ -1,10 +1,16 @@
 use druid_shell::piet::{Piet, Color};
 use druid_shell::kurbo::{Size, Rect};
 use crate::state::key::Key;
-use crate::widgets::raw::{Wrapper, WrapperWidget, PrefSizeWrapper, Padding};
+use crate::widgets::raw::{Wrapper, WrapperWidget, PrefSizeWrapper, Padding, ContextMenu, KeyBindings};
+use crate::app::menu::MenuDesc;
+use crate::app::command::Keymap;
+use crate::state::CloneState;
+use crate::widgets::{Tooltip, TooltipContent, DragSource, DropTarget};
+use druid_shell::kurbo::Point;
+use std::any::Any;
 use crate::widgets::style::{BackgroundWrapper, background};
 use crate::app::widget_graph::WidgetContext;
-use crate::app::event::{EventResponse, Event, Change};
+use crate::app::event::{EventResponse, Event, Change, Lifecycle};
 use crate::app::size::PrefSize;
 
 pub trait Widget<T: Clone> {
@@ -22,6 +28,13 @@ pub trait Widget<T: Clone> {
 
     #[allow(unused_variables)]
     fn traverse_focus(&mut self, context: WidgetContext) -> bool {false}
+
+    /// Notifies the Widget about changes in its lifecycle
+    /// Widgets owning timers, tasks or external subscriptions can acquire and release them here.
+    /// Widgets with children have to forward broadcast notifications to all of them, containers
+    /// adding or removing children later send WidgetAdded and WidgetRemoved themselves.
+    #[allow(unused_variables)]
+    fn lifecycle(&mut self, event: &Lifecycle, context: WidgetContext) {}
 }
 
 impl<T: Clone, W: Widget<T>> WidgetCompose<T> for W {}
@@ -46,6 +59,21 @@ pub trait WidgetCompose<T: Clone>: Widget<T> where Self: Sized {
     fn set_size(self, pref: PrefSize) -> PrefSizeWrapper<Self> {
         PrefSizeWrapper::new(self, pref, false, false, true)
     }
+    fn context_menu(self, menu: MenuDesc) -> ContextMenu<Self> {
+        ContextMenu::new(self, menu)
+    }
+    fn key_bindings(self, keymap: &CloneState<Keymap>) -> KeyBindings<Self> {
+        KeyBindings::new(self, keymap)
+    }
+    fn tooltip(self, content: impl Into<TooltipContent>) -> Tooltip<Self> {
+        Tooltip::new(self, content)
+    }
+    fn drag_source<P: Any>(self, payload: impl FnMut(&T) -> Option<P> + 'static) -> DragSource<T, Self, P> {
+        DragSource::new(self, payload)
+    }
+    fn drop_target<P: Any>(self, on_drop: impl FnMut(P, Point, Key<T>) + 'static) -> DropTarget<T, Self, P> {
+        DropTarget::new(self, on_drop)
+    }
 }
 
 pub trait IntoWidget<T: Clone> {